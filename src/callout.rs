@@ -0,0 +1,450 @@
+use crate::width::display_width;
+use colored::Color;
+
+/// How a callout type is drawn: the icon and label in its header, in its color
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalloutStyle {
+    pub icon: String,
+    pub color: Color,
+    pub label: String,
+}
+
+impl CalloutStyle {
+    pub fn new(icon: impl Into<String>, color: Color, label: impl Into<String>) -> Self {
+        Self {
+            icon: icon.into(),
+            color,
+            label: label.into(),
+        }
+    }
+}
+
+/// Built-in callout types: the five GitHub alerts followed by the other Obsidian types
+const BUILTIN_TYPES: &[(&str, &str, Color, &str)] = &[
+    ("NOTE", "ℹ️", Color::Blue, "Note"),
+    ("TIP", "💡", Color::Green, "Tip"),
+    ("IMPORTANT", "❗", Color::Magenta, "Important"),
+    ("WARNING", "⚠️", Color::Yellow, "Warning"),
+    ("CAUTION", "🔥", Color::Red, "Caution"),
+    ("ABSTRACT", "📋", Color::Cyan, "Abstract"),
+    ("INFO", "ℹ️", Color::Blue, "Info"),
+    ("TODO", "☑️", Color::Blue, "Todo"),
+    ("SUCCESS", "✅", Color::Green, "Success"),
+    ("QUESTION", "❓", Color::Yellow, "Question"),
+    ("FAILURE", "❌", Color::Red, "Failure"),
+    ("DANGER", "⚡", Color::Red, "Danger"),
+    ("BUG", "🐛", Color::Red, "Bug"),
+    ("EXAMPLE", "📝", Color::Magenta, "Example"),
+    ("QUOTE", "💬", Color::BrightBlack, "Quote"),
+];
+
+/// Obsidian's alternative names for its callout types
+const BUILTIN_ALIASES: &[(&str, &str)] = &[
+    ("SUMMARY", "ABSTRACT"),
+    ("TLDR", "ABSTRACT"),
+    ("HINT", "TIP"),
+    ("CHECK", "SUCCESS"),
+    ("DONE", "SUCCESS"),
+    ("HELP", "QUESTION"),
+    ("FAQ", "QUESTION"),
+    ("ATTENTION", "WARNING"),
+    ("FAIL", "FAILURE"),
+    ("MISSING", "FAILURE"),
+    ("ERROR", "DANGER"),
+    ("CITE", "QUOTE"),
+];
+
+/// The callout types recognized in `> [!TYPE]` blockquotes.
+///
+/// Names and aliases match case-insensitively. Types added later replace built-in types of the
+/// same name.
+///
+/// # Examples
+///
+/// ```rust
+/// use colored::Color;
+/// use mqv::{CalloutStyle, Callouts, RenderOptions};
+///
+/// let options = RenderOptions {
+///     callouts: Callouts::default()
+///         .with_type("DECISION", CalloutStyle::new("⚖️", Color::Cyan, "Decision"))
+///         .with_alias("ADR", "DECISION"),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Callouts {
+    types: Vec<(String, CalloutStyle)>,
+    aliases: Vec<(String, String)>,
+}
+
+impl Default for Callouts {
+    fn default() -> Self {
+        Self {
+            types: BUILTIN_TYPES
+                .iter()
+                .map(|(name, icon, color, label)| {
+                    (name.to_string(), CalloutStyle::new(*icon, *color, *label))
+                })
+                .collect(),
+            aliases: BUILTIN_ALIASES
+                .iter()
+                .map(|(alias, name)| (alias.to_string(), name.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl Callouts {
+    /// Add a callout type, or restyle an existing one
+    pub fn with_type(mut self, name: impl Into<String>, style: CalloutStyle) -> Self {
+        let name = name.into();
+        self.types.retain(|(n, _)| !n.eq_ignore_ascii_case(&name));
+        self.types.push((name, style));
+        self
+    }
+
+    /// Make `alias` another name for the callout type `name`
+    pub fn with_alias(mut self, alias: impl Into<String>, name: impl Into<String>) -> Self {
+        let alias = alias.into();
+        self.aliases
+            .retain(|(a, _)| !a.eq_ignore_ascii_case(&alias));
+        self.aliases.push((alias, name.into()));
+        self
+    }
+
+    /// Style of a callout type, following aliases
+    pub(crate) fn get(&self, name: &str) -> Option<&CalloutStyle> {
+        let name = self
+            .aliases
+            .iter()
+            .find(|(alias, _)| alias.eq_ignore_ascii_case(name))
+            .map_or(name, |(_, target)| target.as_str());
+        self.types
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, style)| style)
+    }
+
+    /// Recognize a `[!TYPE]` marker at the start of a callout's first paragraph
+    pub(crate) fn detect<'a>(&'a self, text: &'a str) -> Option<CalloutMarker<'a>> {
+        let inner = text.trim_start().strip_prefix("[!")?;
+        let end = inner.find(']')?;
+        let style = self.get(&inner[..end])?;
+
+        let rest = &inner[end + 1..];
+        let (fold, rest) = match rest.chars().next() {
+            Some('-') => (Some(Fold::Collapsed), &rest[1..]),
+            Some('+') => (Some(Fold::Expanded), &rest[1..]),
+            _ => (None, rest),
+        };
+        let (title, body) = rest.split_once('\n').unwrap_or((rest, ""));
+        let title = title.trim();
+
+        Some(CalloutMarker {
+            style,
+            fold,
+            title: (!title.is_empty()).then_some(title),
+            body,
+        })
+    }
+
+    /// Recognize the opening line of a MkDocs (`!!! note "Title"`, `??? tip`, `???+ tip`) or
+    /// Docusaurus/VitePress (`:::warning Title`, `:::tip[Title]`) admonition
+    pub(crate) fn detect_admonition<'a>(&'a self, line: &'a str) -> Option<Admonition<'a>> {
+        let line = line.trim();
+        if let Some((fold, rest)) = [
+            ("!!!", None),
+            ("???+", Some(Fold::Expanded)),
+            ("???", Some(Fold::Collapsed)),
+        ]
+        .iter()
+        .find_map(|(marker, fold)| line.strip_prefix(marker).map(|rest| (*fold, rest)))
+        {
+            if !rest.starts_with(char::is_whitespace) {
+                return None;
+            }
+            let rest = rest.trim_start();
+            let kind_end = rest
+                .find(|c: char| c.is_whitespace() || c == '"')
+                .unwrap_or(rest.len());
+            // `"Title"` is optional, and `""` hides the title
+            let title = rest[kind_end..]
+                .find('"')
+                .zip(rest.rfind('"'))
+                .filter(|(open, close)| open + kind_end < *close)
+                .map(|(open, close)| rest[kind_end + open + 1..close].trim());
+            return Some(Admonition {
+                style: self.get(&rest[..kind_end])?,
+                fold,
+                title: title.filter(|title| !title.is_empty()),
+                fence: AdmonitionFence::Indented,
+            });
+        }
+
+        let colons = line.chars().take_while(|c| *c == ':').count();
+        if colons < 3 {
+            return None;
+        }
+        let rest = line[colons..].trim_start();
+        let kind_end = rest
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '-' && c != '_')
+            .unwrap_or(rest.len());
+        let after = rest[kind_end..].trim();
+        let title = match after.strip_prefix('[') {
+            Some(bracketed) => bracketed.split_once(']').map(|(title, _)| title.trim()),
+            None => Some(after),
+        };
+        Some(Admonition {
+            style: self.get(&rest[..kind_end])?,
+            fold: None,
+            title: title.filter(|title| !title.is_empty()),
+            fence: AdmonitionFence::Colons(colons),
+        })
+    }
+}
+
+/// How the body of an admonition is delimited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AdmonitionFence {
+    /// MkDocs: the body is indented under the opening line
+    Indented,
+    /// Docusaurus/VitePress: the body runs to a line of at least this many colons
+    Colons(usize),
+}
+
+/// A parsed admonition opening line, rendered as a callout of its type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Admonition<'a> {
+    pub(crate) style: &'a CalloutStyle,
+    pub(crate) fold: Option<Fold>,
+    pub(crate) title: Option<&'a str>,
+    pub(crate) fence: AdmonitionFence,
+}
+
+/// Whether a line closes a `:::` admonition opened with `colons` colons
+pub(crate) fn is_closing_fence(line: &str, colons: usize) -> bool {
+    let line = line.trim();
+    line.len() >= colons && line.chars().all(|c| c == ':')
+}
+
+/// Whether a foldable callout (`[!note]-` / `[!note]+`) starts collapsed or expanded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Fold {
+    Collapsed,
+    Expanded,
+}
+
+/// A parsed `[!TYPE]± Title` callout marker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CalloutMarker<'a> {
+    pub(crate) style: &'a CalloutStyle,
+    pub(crate) fold: Option<Fold>,
+    /// Text after the marker on its line, shown after the type's label
+    pub(crate) title: Option<&'a str>,
+    /// Text on the lines after the marker
+    pub(crate) body: &'a str,
+}
+
+/// How the box around callouts is drawn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CalloutBox {
+    /// Only a left border, open on the right
+    #[default]
+    Open,
+    /// A closed box just wide enough for its content
+    Fit,
+    /// A closed box spanning the layout width
+    Full,
+}
+
+/// Frame body lines, which already start with the `│ ` left border, under a `┌─ header`,
+/// closing the box on the right unless it is [`CalloutBox::Open`]. Lines wider than a closed
+/// box are left unpadded.
+pub(crate) fn box_lines(header: &str, body: &str, frame: CalloutBox, width: usize) -> Vec<String> {
+    let body: Vec<&str> = body.trim_matches('\n').lines().collect();
+    let header_width = display_width(header);
+
+    let box_width = match frame {
+        CalloutBox::Open => {
+            let mut lines = vec![format!("┌─ {}", header)];
+            lines.extend(body.iter().map(|line| line.to_string()));
+            lines.push("└─".to_string());
+            return lines;
+        }
+        CalloutBox::Fit => body
+            .iter()
+            .map(|line| display_width(line) + 2)
+            .max()
+            .unwrap_or(0),
+        CalloutBox::Full => width,
+    }
+    .max(header_width + 6);
+    let inner_width = box_width - 2;
+
+    let mut lines = vec![format!(
+        "┌─ {} {}┐",
+        header,
+        "─".repeat(box_width - 5 - header_width)
+    )];
+    lines.extend(body.iter().map(|line| {
+        let padding = inner_width.saturating_sub(display_width(line));
+        format!("{}{} │", line, " ".repeat(padding))
+    }));
+    lines.push(format!("└{}┘", "─".repeat(box_width - 2)));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("[!NOTE]", "Note")]
+    #[case("[!note]", "Note")]
+    #[case("[!info]", "Info")]
+    #[case("[!bug]", "Bug")]
+    #[case("[!example]", "Example")]
+    #[case("[!quote]", "Quote")]
+    #[case("[!tldr]", "Abstract")]
+    #[case("[!Error]", "Danger")]
+    fn test_detect_builtin_types(#[case] text: &str, #[case] label: &str) {
+        let callouts = Callouts::default();
+        assert_eq!(callouts.detect(text).unwrap().style.label, label);
+    }
+
+    #[rstest]
+    #[case("Regular text")]
+    #[case("[NOTE] No exclamation")]
+    #[case("[!unknown] Not a type")]
+    fn test_detect_none(#[case] text: &str) {
+        assert!(Callouts::default().detect(text).is_none());
+    }
+
+    #[rstest]
+    #[case(
+        "[!warning] Breaking change\nBody",
+        None,
+        Some("Breaking change"),
+        "Body"
+    )]
+    #[case("[!note]-\nHidden", Some(Fold::Collapsed), None, "Hidden")]
+    #[case("[!note]+ Open title", Some(Fold::Expanded), Some("Open title"), "")]
+    #[case("[!tip]", None, None, "")]
+    fn test_detect_title_and_fold(
+        #[case] text: &str,
+        #[case] fold: Option<Fold>,
+        #[case] title: Option<&str>,
+        #[case] body: &str,
+    ) {
+        let callouts = Callouts::default();
+        let marker = callouts.detect(text).unwrap();
+        assert_eq!(marker.fold, fold);
+        assert_eq!(marker.title, title);
+        assert_eq!(marker.body, body);
+    }
+
+    #[rstest]
+    #[case(
+        "!!! note \"Heads up\"",
+        "Note",
+        None,
+        Some("Heads up"),
+        AdmonitionFence::Indented
+    )]
+    #[case("!!! warning", "Warning", None, None, AdmonitionFence::Indented)]
+    #[case(
+        "??? tip",
+        "Tip",
+        Some(Fold::Collapsed),
+        None,
+        AdmonitionFence::Indented
+    )]
+    #[case(
+        "???+ bug \"Known issue\"",
+        "Bug",
+        Some(Fold::Expanded),
+        Some("Known issue"),
+        AdmonitionFence::Indented
+    )]
+    #[case("!!! example \"\"", "Example", None, None, AdmonitionFence::Indented)]
+    #[case(":::warning", "Warning", None, None, AdmonitionFence::Colons(3))]
+    #[case(
+        "::: danger Be careful",
+        "Danger",
+        None,
+        Some("Be careful"),
+        AdmonitionFence::Colons(3)
+    )]
+    #[case(
+        "::::tip[Pro tip]",
+        "Tip",
+        None,
+        Some("Pro tip"),
+        AdmonitionFence::Colons(4)
+    )]
+    fn test_detect_admonition(
+        #[case] line: &str,
+        #[case] label: &str,
+        #[case] fold: Option<Fold>,
+        #[case] title: Option<&str>,
+        #[case] fence: AdmonitionFence,
+    ) {
+        let callouts = Callouts::default();
+        let admonition = callouts.detect_admonition(line).unwrap();
+        assert_eq!(admonition.style.label, label);
+        assert_eq!(admonition.fold, fold);
+        assert_eq!(admonition.title, title);
+        assert_eq!(admonition.fence, fence);
+    }
+
+    #[rstest]
+    #[case("!!!note")]
+    #[case("!!! unknown")]
+    #[case(":: note")]
+    #[case(":::")]
+    #[case("Regular text")]
+    fn test_detect_admonition_none(#[case] line: &str) {
+        assert!(Callouts::default().detect_admonition(line).is_none());
+    }
+
+    #[rstest]
+    #[case(":::", 3, true)]
+    #[case("::::", 3, true)]
+    #[case(":::", 4, false)]
+    #[case("::: x", 3, false)]
+    fn test_is_closing_fence(#[case] line: &str, #[case] colons: usize, #[case] expected: bool) {
+        assert_eq!(is_closing_fence(line, colons), expected);
+    }
+
+    #[test]
+    fn test_box_lines_open() {
+        assert_eq!(
+            box_lines("Note", "│ a\n│ bc\n", CalloutBox::Open, 80),
+            vec!["┌─ Note", "│ a", "│ bc", "└─"]
+        );
+    }
+
+    #[rstest]
+    #[case(CalloutBox::Fit, 10, vec!["┌─ Note ─┐", "│ a      │", "│        │", "│ bcdef  │", "└────────┘"])]
+    #[case(CalloutBox::Full, 12, vec!["┌─ Note ───┐", "│ a        │", "│          │", "│ bcdef    │", "└──────────┘"])]
+    fn test_box_lines_closed(
+        #[case] frame: CalloutBox,
+        #[case] width: usize,
+        #[case] expected: Vec<&str>,
+    ) {
+        assert_eq!(box_lines("Note", "│ a\n│\n│ bcdef", frame, width), expected);
+    }
+
+    #[test]
+    fn test_custom_types_and_aliases() {
+        let callouts = Callouts::default()
+            .with_type("DECISION", CalloutStyle::new("⚖️", Color::Cyan, "Decision"))
+            .with_type("note", CalloutStyle::new("📌", Color::White, "Memo"))
+            .with_alias("adr", "decision");
+        assert_eq!(callouts.get("Decision").unwrap().icon, "⚖️");
+        assert_eq!(callouts.get("ADR").unwrap().label, "Decision");
+        assert_eq!(callouts.get("NOTE").unwrap().label, "Memo");
+    }
+}
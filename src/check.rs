@@ -0,0 +1,97 @@
+use crate::highlighter::{SyntaxError, SyntaxHighlighter};
+use mq_markdown::{Markdown, Node};
+
+/// A fenced code block whose declared language does not parse cleanly
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlockDiagnostic {
+    /// Language declared on the fence
+    pub lang: String,
+    /// 1-based line of the opening fence in the Markdown source, if known
+    pub line: Option<usize>,
+    /// Syntax errors reported by tree-sitter, with lines relative to the block
+    pub errors: Vec<SyntaxError>,
+}
+
+impl CodeBlockDiagnostic {
+    /// 1-based line of a block error in the Markdown source, if the block position is known
+    pub fn source_line(&self, error: &SyntaxError) -> Option<usize> {
+        // The first line of code follows the opening fence
+        self.line.map(|line| line + error.line)
+    }
+}
+
+/// Parse every fenced code block with a supported language and report the ones with syntax errors.
+///
+/// Blocks without a language or with a language tree-sitter does not support are skipped.
+///
+/// # Examples
+///
+/// ```rust
+/// use mqv::check_code_blocks;
+/// use mq_markdown::Markdown;
+///
+/// let markdown: Markdown = "```rust\nfn main( {}\n```".parse().unwrap();
+/// let diagnostics = check_code_blocks(&markdown);
+/// assert_eq!(diagnostics.len(), 1);
+/// ```
+pub fn check_code_blocks(markdown: &Markdown) -> Vec<CodeBlockDiagnostic> {
+    let mut diagnostics = Vec::new();
+    check_nodes(&markdown.nodes, &mut diagnostics);
+    diagnostics
+}
+
+fn check_nodes(nodes: &[Node], diagnostics: &mut Vec<CodeBlockDiagnostic>) {
+    for node in nodes {
+        match node {
+            Node::Code(code) => {
+                let Some(lang) = code.lang.as_deref() else {
+                    continue;
+                };
+                if let Some(errors) = SyntaxHighlighter::syntax_errors(&code.value, lang)
+                    && !errors.is_empty()
+                {
+                    diagnostics.push(CodeBlockDiagnostic {
+                        lang: lang.to_string(),
+                        line: code.position.as_ref().map(|p| p.start.line),
+                        errors,
+                    });
+                }
+            }
+            Node::Blockquote(blockquote) => check_nodes(&blockquote.values, diagnostics),
+            Node::List(list) => check_nodes(&list.values, diagnostics),
+            Node::Fragment(fragment) => check_nodes(&fragment.values, diagnostics),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_code_blocks_reports_broken_block() {
+        let markdown: Markdown =
+            "# Title\n\n```rust\nfn main() {}\n```\n\n```rust\nfn main( {}\n```"
+                .parse()
+                .unwrap();
+        let diagnostics = check_code_blocks(&markdown);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].lang, "rust");
+        assert!(!diagnostics[0].errors.is_empty());
+    }
+
+    #[test]
+    fn test_check_code_blocks_skips_unknown_languages() {
+        let markdown: Markdown = "```text\nfn main( {}\n```\n\n```\n{{{\n```"
+            .parse()
+            .unwrap();
+        assert!(check_code_blocks(&markdown).is_empty());
+    }
+
+    #[test]
+    fn test_check_code_blocks_inside_list() {
+        let markdown: Markdown = "- step\n\n  ```json\n  {\"a\": }\n  ```".parse().unwrap();
+        assert_eq!(check_code_blocks(&markdown).len(), 1);
+    }
+}
@@ -0,0 +1,79 @@
+use mq_markdown::{Definition, Markdown, Node};
+use std::collections::HashMap;
+
+/// Link reference definitions (`[ref]: https://…`) of a document, keyed by normalized label
+#[derive(Debug, Default)]
+pub(crate) struct Definitions<'a> {
+    definitions: HashMap<String, &'a Definition>,
+}
+
+impl<'a> Definitions<'a> {
+    pub(crate) fn collect(markdown: &'a Markdown) -> Self {
+        let mut definitions = HashMap::new();
+        walk(&markdown.nodes, &mut definitions);
+        Self { definitions }
+    }
+
+    /// URL of the definition matching a reference identifier, if there is one
+    pub(crate) fn url(&self, ident: &str) -> Option<&'a str> {
+        self.definitions
+            .get(&normalize(ident))
+            .map(|definition| definition.url.as_str())
+    }
+}
+
+/// Labels match case-insensitively and regardless of whitespace, so identifiers compare equal
+/// however the parser normalized them
+fn normalize(label: &str) -> String {
+    label
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn walk<'a>(nodes: &'a [Node], definitions: &mut HashMap<String, &'a Definition>) {
+    for node in nodes {
+        let children = match node {
+            Node::Definition(definition) => {
+                // The first definition of a label wins
+                definitions
+                    .entry(normalize(&definition.ident))
+                    .or_insert(definition);
+                continue;
+            }
+            Node::Fragment(fragment) => &fragment.values,
+            Node::Blockquote(blockquote) => &blockquote.values,
+            Node::List(list) => &list.values,
+            Node::Footnote(footnote) => &footnote.values,
+            _ => continue,
+        };
+        walk(children, definitions);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("docs", Some("https://example.com/docs"))]
+    #[case("DOCS", Some("https://example.com/docs"))]
+    #[case("Two  Words", Some("https://example.com/two"))]
+    #[case("missing", None)]
+    fn test_url(#[case] ident: &str, #[case] expected: Option<&str>) {
+        let markdown: Markdown = "See [docs][] and [two words].\n\n[docs]: https://example.com/docs\n[two words]: https://example.com/two\n[docs]: https://example.com/ignored"
+            .parse()
+            .unwrap();
+        let definitions = Definitions::collect(&markdown);
+        assert_eq!(definitions.url(ident), expected);
+    }
+
+    #[test]
+    fn test_collect_inside_blockquote() {
+        let markdown: Markdown = "> [a]: https://example.com/a".parse().unwrap();
+        let definitions = Definitions::collect(&markdown);
+        assert_eq!(definitions.url("a"), Some("https://example.com/a"));
+    }
+}
@@ -0,0 +1,207 @@
+use crate::highlighter::SyntaxHighlighter;
+use colored::*;
+use std::path::Path;
+
+/// Background for added lines (dark green)
+const ADDED_BACKGROUND: &str = "\x1b[48;5;22m";
+/// Background for removed lines (dark red)
+const REMOVED_BACKGROUND: &str = "\x1b[48;5;52m";
+const RESET: &str = "\x1b[0m";
+
+/// Kind of a single line in a unified diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffLine {
+    FileHeader,
+    HunkHeader,
+    Added,
+    Removed,
+    Context,
+}
+
+/// Returns true if a fenced code block language denotes a diff or patch
+pub(crate) fn is_diff_language(lang: &str) -> bool {
+    matches!(lang.to_lowercase().as_str(), "diff" | "patch")
+}
+
+/// Render a unified diff with colored line backgrounds.
+///
+/// File headers are bold and hunk headers are dimmed. When the file extension of the
+/// current file is known, changed and context lines are syntax highlighted as well.
+pub(crate) fn render_diff(code: &str, highlighter: &mut SyntaxHighlighter) -> String {
+    let mut lines = Vec::new();
+    let mut lang: Option<&'static str> = None;
+    let mut in_hunk = false;
+
+    for line in code.lines() {
+        let rendered = match classify_line(line, in_hunk) {
+            DiffLine::FileHeader => {
+                if line.starts_with("diff ") {
+                    in_hunk = false;
+                    lang = None;
+                }
+                if let Some(detected) = header_path(line).and_then(language_from_path) {
+                    lang = Some(detected);
+                }
+                line.bold().to_string()
+            }
+            DiffLine::HunkHeader => {
+                in_hunk = true;
+                line.dimmed().to_string()
+            }
+            DiffLine::Added => {
+                paint_changed_line('+', &line[1..], ADDED_BACKGROUND, lang, highlighter)
+            }
+            DiffLine::Removed => {
+                paint_changed_line('-', &line[1..], REMOVED_BACKGROUND, lang, highlighter)
+            }
+            DiffLine::Context => match line.strip_prefix(' ') {
+                Some(body) => format!(" {}", highlighter.highlight(body, lang)),
+                None => line.dimmed().to_string(),
+            },
+        };
+        lines.push(rendered);
+    }
+
+    lines.join("\n")
+}
+
+fn classify_line(line: &str, in_hunk: bool) -> DiffLine {
+    const FILE_HEADER_PREFIXES: &[&str] = &[
+        "diff ",
+        "index ",
+        "new file mode",
+        "deleted file mode",
+        "old mode",
+        "new mode",
+        "similarity index",
+        "rename from",
+        "rename to",
+        "Binary files",
+    ];
+
+    if line.starts_with("@@") {
+        DiffLine::HunkHeader
+    } else if FILE_HEADER_PREFIXES.iter().any(|p| line.starts_with(p)) {
+        DiffLine::FileHeader
+    } else if !in_hunk && (line.starts_with("--- ") || line.starts_with("+++ ")) {
+        // Inside a hunk, "--- x" is a removed line whose content starts with "-- "
+        DiffLine::FileHeader
+    } else if line.starts_with('+') {
+        DiffLine::Added
+    } else if line.starts_with('-') {
+        DiffLine::Removed
+    } else {
+        DiffLine::Context
+    }
+}
+
+/// Extract the file path from a `diff --git`, `---` or `+++` header line
+fn header_path(line: &str) -> Option<&str> {
+    let path = if let Some(rest) = line.strip_prefix("diff ") {
+        rest.split_whitespace().last()?
+    } else if let Some(rest) = line
+        .strip_prefix("+++ ")
+        .or_else(|| line.strip_prefix("--- "))
+    {
+        // Some tools append a tab-separated timestamp after the path
+        rest.split('\t').next()?.trim()
+    } else {
+        return None;
+    };
+
+    if path == "/dev/null" {
+        return None;
+    }
+
+    Some(
+        path.strip_prefix("a/")
+            .or_else(|| path.strip_prefix("b/"))
+            .unwrap_or(path),
+    )
+}
+
+fn language_from_path(path: &str) -> Option<&'static str> {
+    let ext = Path::new(path).extension()?.to_str()?;
+    SyntaxHighlighter::language_for_extension(ext)
+}
+
+fn paint_changed_line(
+    marker: char,
+    body: &str,
+    background: &str,
+    lang: Option<&str>,
+    highlighter: &mut SyntaxHighlighter,
+) -> String {
+    let body = match lang {
+        Some(lang) => highlighter.highlight(body, Some(lang)),
+        None => {
+            let foreground = if marker == '+' {
+                "\x1b[32m"
+            } else {
+                "\x1b[31m"
+            };
+            format!("{}{}", foreground, body)
+        }
+    };
+    // The highlighter resets all attributes after each token, so restore the background
+    let body = body.replace(RESET, &format!("{}{}", RESET, background));
+    format!("{}{}{}{}", background, marker, body, RESET)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("diff", true)]
+    #[case("PATCH", true)]
+    #[case("rust", false)]
+    fn test_is_diff_language(#[case] lang: &str, #[case] expected: bool) {
+        assert_eq!(is_diff_language(lang), expected);
+    }
+
+    #[rstest]
+    #[case("diff --git a/src/main.rs b/src/main.rs", false, DiffLine::FileHeader)]
+    #[case("+++ b/src/main.rs", false, DiffLine::FileHeader)]
+    #[case("--- a/src/main.rs", false, DiffLine::FileHeader)]
+    #[case("--- removed comment", true, DiffLine::Removed)]
+    #[case("@@ -1,3 +1,4 @@", true, DiffLine::HunkHeader)]
+    #[case("+let x = 1;", true, DiffLine::Added)]
+    #[case("-let x = 2;", true, DiffLine::Removed)]
+    #[case(" let y = 3;", true, DiffLine::Context)]
+    fn test_classify_line(#[case] line: &str, #[case] in_hunk: bool, #[case] expected: DiffLine) {
+        assert_eq!(classify_line(line, in_hunk), expected);
+    }
+
+    #[rstest]
+    #[case("diff --git a/src/lib.rs b/src/lib.rs", Some("src/lib.rs"))]
+    #[case("+++ b/app.py\t2024-01-01", Some("app.py"))]
+    #[case("--- /dev/null", None)]
+    #[case("@@ -1 +1 @@", None)]
+    fn test_header_path(#[case] line: &str, #[case] expected: Option<&str>) {
+        assert_eq!(header_path(line), expected);
+    }
+
+    #[test]
+    fn test_render_diff_backgrounds() {
+        let mut highlighter = SyntaxHighlighter::new();
+        let diff = "@@ -1 +1 @@\n-old\n+new\n context";
+        let result = render_diff(diff, &mut highlighter);
+        assert!(result.contains(&format!("{}-", REMOVED_BACKGROUND)));
+        assert!(result.contains(&format!("{}+", ADDED_BACKGROUND)));
+        assert!(result.contains("context"));
+        assert_eq!(result.lines().count(), 4);
+    }
+
+    #[test]
+    fn test_render_diff_highlights_known_language() {
+        let mut highlighter = SyntaxHighlighter::new();
+        let diff = "--- a/main.rs\n+++ b/main.rs\n@@ -1 +1 @@\n+fn main() {}";
+        let result = render_diff(diff, &mut highlighter);
+        let added = result.lines().last().unwrap();
+        // Highlighted tokens must keep the added background after each reset
+        assert!(added.contains(&format!("{}{}", RESET, ADDED_BACKGROUND)));
+        assert!(added.contains("main"));
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+const HALF_BLOCK_FONT: &str = include_str!("../assets/fonts/halfblock.flf");
+const BLOCK_FONT: &str = include_str!("../assets/fonts/block.flf");
+
+/// FIGlet fonts bundled for banner headings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BannerFont {
+    /// Three rows tall, drawn with Unicode half blocks (`▀▄█`)
+    #[default]
+    HalfBlock,
+    /// Five rows tall, drawn with full blocks (`█`)
+    Block,
+}
+
+/// A parsed FIGlet (`.flf`) font, laid out at full width without smushing
+#[derive(Debug, Clone)]
+pub(crate) struct FigletFont {
+    height: usize,
+    glyphs: HashMap<char, Vec<String>>,
+}
+
+impl FigletFont {
+    /// A bundled font, parsed on first use
+    pub(crate) fn bundled(font: BannerFont) -> &'static Self {
+        static HALF_BLOCK: OnceLock<FigletFont> = OnceLock::new();
+        static BLOCK: OnceLock<FigletFont> = OnceLock::new();
+
+        let (cell, source) = match font {
+            BannerFont::HalfBlock => (&HALF_BLOCK, HALF_BLOCK_FONT),
+            BannerFont::Block => (&BLOCK, BLOCK_FONT),
+        };
+        cell.get_or_init(|| Self::parse(source).expect("bundled FIGlet font is valid"))
+    }
+
+    /// Parse the required ASCII characters (32-126) of a FIGlet font
+    pub(crate) fn parse(source: &str) -> Option<Self> {
+        let mut lines = source.lines();
+        let header = lines.next()?.strip_prefix("flf2a")?;
+        let hardblank = header.chars().next()?;
+        let params: Vec<usize> = header[hardblank.len_utf8()..]
+            .split_whitespace()
+            .map(|p| p.parse().unwrap_or(0))
+            .collect();
+        let height = *params.first().filter(|h| **h > 0)?;
+        let comment_lines = params.get(4).copied().unwrap_or(0);
+
+        for _ in 0..comment_lines {
+            lines.next()?;
+        }
+
+        let mut glyphs = HashMap::new();
+        for code in 32u8..=126 {
+            let mut glyph = Vec::with_capacity(height);
+            for _ in 0..height {
+                let line = lines.next()?;
+                let endmark = line.chars().last()?;
+                let line = line.trim_end_matches(endmark).replace(hardblank, " ");
+                glyph.push(line);
+            }
+            glyphs.insert(code as char, glyph);
+        }
+
+        Some(Self { height, glyphs })
+    }
+
+    /// Render text on a single banner, or `None` if a character has no glyph
+    pub(crate) fn render(&self, text: &str) -> Option<Vec<String>> {
+        let mut rows = vec![String::new(); self.height];
+        for c in text.chars() {
+            let glyph = self.glyphs.get(&c)?;
+            for (row, line) in rows.iter_mut().zip(glyph) {
+                row.push_str(line);
+            }
+        }
+        Some(
+            rows.into_iter()
+                .map(|row| row.trim_end().to_string())
+                .collect(),
+        )
+    }
+
+    /// Render text wrapped at word boundaries so that no banner is wider than `width`.
+    ///
+    /// Returns `None` if a character has no glyph or a single word does not fit.
+    pub(crate) fn render_wrapped(&self, text: &str, width: usize) -> Option<Vec<String>> {
+        let mut lines = Vec::new();
+        let mut current = String::new();
+
+        for word in text.split_whitespace() {
+            let candidate = if current.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", current, word)
+            };
+
+            if self.text_width(&candidate)? <= width {
+                current = candidate;
+            } else if self.text_width(word)? <= width && !current.is_empty() {
+                lines.push(std::mem::replace(&mut current, word.to_string()));
+            } else {
+                return None;
+            }
+        }
+        if !current.is_empty() {
+            lines.push(current);
+        }
+
+        let mut rows = Vec::new();
+        for line in lines {
+            rows.extend(self.render(&line)?);
+        }
+        Some(rows)
+    }
+
+    fn text_width(&self, text: &str) -> Option<usize> {
+        text.chars()
+            .map(|c| {
+                self.glyphs
+                    .get(&c)
+                    .map(|glyph| glyph.first().map_or(0, |line| line.chars().count()))
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const TINY_FONT: &str = "flf2a$ 1 1 3 -1 1\ncomment\n";
+
+    fn tiny_font() -> FigletFont {
+        // One row per glyph: every character renders as itself followed by a hardblank
+        let mut source = TINY_FONT.to_string();
+        for code in 32u8..=126 {
+            let c = if code == b'@' { '#' } else { code as char };
+            source.push_str(&format!("{}$@@\n", c));
+        }
+        FigletFont::parse(&source).unwrap()
+    }
+
+    #[rstest]
+    #[case(BannerFont::HalfBlock, 3)]
+    #[case(BannerFont::Block, 5)]
+    fn test_bundled_fonts(#[case] font: BannerFont, #[case] height: usize) {
+        let font = FigletFont::bundled(font);
+        let rows = font.render("Hello, World!").unwrap();
+        assert_eq!(rows.len(), height);
+        assert!(rows.iter().any(|row| row.contains('█')));
+    }
+
+    #[test]
+    fn test_parse_invalid_header() {
+        assert!(FigletFont::parse("not a font").is_none());
+        assert!(FigletFont::parse("flf2a$ 0 0 0 0 0").is_none());
+    }
+
+    #[test]
+    fn test_render_replaces_hardblank() {
+        let font = tiny_font();
+        assert_eq!(font.render("ab").unwrap(), vec!["a b"]);
+    }
+
+    #[test]
+    fn test_render_unknown_character() {
+        let font = tiny_font();
+        assert!(font.render("日本").is_none());
+    }
+
+    #[test]
+    fn test_render_wrapped() {
+        let font = tiny_font();
+        // Each glyph is two columns wide
+        assert_eq!(
+            font.render_wrapped("ab cd ef", 10).unwrap(),
+            vec!["a b   c d", "e f"]
+        );
+        assert!(font.render_wrapped("abcdefgh", 10).is_none());
+    }
+}
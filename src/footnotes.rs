@@ -0,0 +1,143 @@
+use mq_markdown::{Footnote, Markdown, Node};
+use std::collections::HashMap;
+
+const SUPERSCRIPT_DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+
+/// Footnote definitions of a document, numbered in order of first reference
+#[derive(Debug, Default)]
+pub(crate) struct Footnotes<'a> {
+    numbers: HashMap<&'a str, usize>,
+    definitions: Vec<(usize, &'a Footnote)>,
+}
+
+impl<'a> Footnotes<'a> {
+    pub(crate) fn collect(markdown: &'a Markdown) -> Self {
+        let mut definitions = HashMap::new();
+        let mut references = Vec::new();
+        walk(&markdown.nodes, &mut definitions, &mut references);
+
+        let mut numbers = HashMap::new();
+        // Referenced footnotes first, then any unreferenced definitions in source order
+        let mut idents: Vec<&str> = references;
+        let mut unreferenced: Vec<(&str, &Footnote)> = definitions
+            .iter()
+            .map(|(ident, footnote)| (*ident, *footnote))
+            .collect();
+        unreferenced.sort_by_key(|(_, footnote)| footnote.position.as_ref().map(|p| p.start.line));
+        idents.extend(unreferenced.into_iter().map(|(ident, _)| ident));
+
+        let mut numbered = Vec::new();
+        for ident in idents {
+            if numbers.contains_key(ident) {
+                continue;
+            }
+            if let Some(footnote) = definitions.get(ident) {
+                let number = numbered.len() + 1;
+                numbers.insert(ident, number);
+                numbered.push((number, *footnote));
+            }
+        }
+
+        Self {
+            numbers,
+            definitions: numbered,
+        }
+    }
+
+    /// Number of the footnote with the given identifier, if it is defined
+    pub(crate) fn number(&self, ident: &str) -> Option<usize> {
+        self.numbers.get(ident).copied()
+    }
+
+    /// Footnote definitions in display order
+    pub(crate) fn definitions(&self) -> &[(usize, &'a Footnote)] {
+        &self.definitions
+    }
+}
+
+fn walk<'a>(
+    nodes: &'a [Node],
+    definitions: &mut HashMap<&'a str, &'a Footnote>,
+    references: &mut Vec<&'a str>,
+) {
+    for node in nodes {
+        let children = match node {
+            Node::Footnote(footnote) => {
+                definitions
+                    .entry(footnote.ident.as_str())
+                    .or_insert(footnote);
+                &footnote.values
+            }
+            Node::FootnoteRef(reference) => {
+                references.push(reference.ident.as_str());
+                continue;
+            }
+            Node::Fragment(fragment) => &fragment.values,
+            Node::Blockquote(blockquote) => &blockquote.values,
+            Node::List(list) => &list.values,
+            Node::Heading(heading) => &heading.values,
+            Node::Strong(strong) => &strong.values,
+            Node::Emphasis(emphasis) => &emphasis.values,
+            Node::Delete(delete) => &delete.values,
+            Node::Link(link) => &link.values,
+            Node::TableCell(cell) => &cell.values,
+            Node::TableRow(row) => &row.values,
+            _ => continue,
+        };
+        walk(children, definitions, references);
+    }
+}
+
+/// Format a number with Unicode superscript digits
+pub(crate) fn superscript(number: usize) -> String {
+    number
+        .to_string()
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .map(|d| SUPERSCRIPT_DIGITS[d as usize])
+        .collect()
+}
+
+/// Target of the OSC 8 links between a footnote's references and its definition.
+///
+/// Terminals do not resolve fragment-only URIs, so the link only tells a pager which
+/// definition a reference belongs to; the definition's number carries the same target.
+pub(crate) fn definition_anchor(number: usize) -> String {
+    format!("#footnote-{}", number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(1, "¹")]
+    #[case(10, "¹⁰")]
+    #[case(234, "²³⁴")]
+    fn test_superscript(#[case] number: usize, #[case] expected: &str) {
+        assert_eq!(superscript(number), expected);
+    }
+
+    #[test]
+    fn test_collect_numbers_by_first_reference() {
+        let markdown: Markdown = "A[^b] and B[^a] and again[^b].\n\n[^a]: First\n\n[^b]: Second"
+            .parse()
+            .unwrap();
+        let footnotes = Footnotes::collect(&markdown);
+        assert_eq!(footnotes.number("b"), Some(1));
+        assert_eq!(footnotes.number("a"), Some(2));
+        assert_eq!(footnotes.definitions().len(), 2);
+        assert_eq!(footnotes.definitions()[0].1.ident, "b");
+    }
+
+    #[test]
+    fn test_collect_undefined_and_unreferenced() {
+        let markdown: Markdown = "Missing[^x].\n\n[^unused]: Never referenced"
+            .parse()
+            .unwrap();
+        let footnotes = Footnotes::collect(&markdown);
+        assert_eq!(footnotes.number("x"), None);
+        assert_eq!(footnotes.number("unused"), Some(1));
+    }
+}
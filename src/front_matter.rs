@@ -0,0 +1,359 @@
+use crate::width::display_width;
+use colored::*;
+
+/// Keys shown first in the metadata panel, in this order
+const PRIORITY_KEYS: &[&str] = &["title", "author", "authors", "date", "tags"];
+
+/// How YAML (`---`) and TOML (`+++`) front matter is displayed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FrontMatterMode {
+    /// Do not show front matter
+    Hide,
+    /// Show the front matter source with key/value highlighting
+    Raw,
+    /// Show the title, author, date, tags and other top-level keys in a metadata box
+    #[default]
+    Panel,
+}
+
+/// Syntax of a front matter block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FrontMatterFormat {
+    Yaml,
+    Toml,
+}
+
+impl FrontMatterFormat {
+    fn fence(self) -> &'static str {
+        match self {
+            FrontMatterFormat::Yaml => "---",
+            FrontMatterFormat::Toml => "+++",
+        }
+    }
+
+    fn separator(self) -> char {
+        match self {
+            FrontMatterFormat::Yaml => ':',
+            FrontMatterFormat::Toml => '=',
+        }
+    }
+}
+
+/// A top-level front matter value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Value {
+    Text(String),
+    List(Vec<String>),
+}
+
+impl Value {
+    fn display(&self) -> String {
+        match self {
+            Value::Text(text) => text.clone(),
+            Value::List(items) => items.join(", "),
+        }
+    }
+}
+
+/// Top-level keys of a front matter block.
+///
+/// Only scalars and lists of scalars are read; nested mappings and TOML tables are skipped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct FrontMatter {
+    entries: Vec<(String, Value)>,
+}
+
+impl FrontMatter {
+    pub(crate) fn parse(source: &str, format: FrontMatterFormat) -> Self {
+        match format {
+            FrontMatterFormat::Yaml => Self::parse_yaml(source),
+            FrontMatterFormat::Toml => Self::parse_toml(source),
+        }
+    }
+
+    fn parse_yaml(source: &str) -> Self {
+        let mut entries: Vec<(String, Value)> = Vec::new();
+
+        for line in source.lines() {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with([' ', '\t', '-']) {
+                // `- item` lines under a key without an inline value form a list
+                if let Some(item) = line.trim().strip_prefix("- ")
+                    && let Some((_, value)) = entries.last_mut()
+                {
+                    match value {
+                        Value::List(items) => items.push(unquote(item)),
+                        Value::Text(text) if text.is_empty() => {
+                            *value = Value::List(vec![unquote(item)])
+                        }
+                        Value::Text(_) => {}
+                    }
+                }
+                continue;
+            }
+
+            if let Some((key, value)) = line.split_once(':') {
+                entries.push((key.trim().to_string(), parse_value(value)));
+            }
+        }
+
+        entries.retain(|(_, value)| *value != Value::Text(String::new()));
+        Self { entries }
+    }
+
+    fn parse_toml(source: &str) -> Self {
+        let mut entries = Vec::new();
+
+        for line in source.lines() {
+            let line = line.trim();
+            if line.starts_with('[') {
+                // Keys after the first table header belong to that table
+                break;
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                entries.push((unquote(key), parse_value(value)));
+            }
+        }
+
+        Self { entries }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<&Value> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value)
+    }
+
+    /// The `title` value, used as the document heading when there is no h1
+    pub(crate) fn title(&self) -> Option<&str> {
+        match self.get("title")? {
+            Value::Text(title) if !title.is_empty() => Some(title),
+            _ => None,
+        }
+    }
+
+    /// Entries in panel order: title, author, date and tags first, then the rest as written
+    pub(crate) fn panel_entries(&self) -> Vec<(&str, &Value)> {
+        let priority = |key: &str| {
+            PRIORITY_KEYS
+                .iter()
+                .position(|k| k.eq_ignore_ascii_case(key))
+                .unwrap_or(PRIORITY_KEYS.len())
+        };
+        let mut entries: Vec<(&str, &Value)> =
+            self.entries.iter().map(|(k, v)| (k.as_str(), v)).collect();
+        entries.sort_by_key(|(key, _)| priority(key));
+        entries
+    }
+}
+
+fn parse_value(value: &str) -> Value {
+    let value = value.trim();
+    match value
+        .strip_prefix('[')
+        .and_then(|inner| inner.strip_suffix(']'))
+    {
+        Some(inner) => Value::List(
+            inner
+                .split(',')
+                .map(unquote)
+                .filter(|item| !item.is_empty())
+                .collect(),
+        ),
+        None => Value::Text(unquote(value)),
+    }
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    ['"', '\'']
+        .iter()
+        .find_map(|quote| {
+            value
+                .strip_prefix(*quote)
+                .and_then(|v| v.strip_suffix(*quote))
+        })
+        .unwrap_or(value)
+        .to_string()
+}
+
+/// Draw the metadata panel as a rounded box of key/value rows
+pub(crate) fn panel_lines(front_matter: &FrontMatter) -> Vec<String> {
+    let entries = front_matter.panel_entries();
+    let key_width = entries
+        .iter()
+        .map(|(key, _)| display_width(key))
+        .max()
+        .unwrap_or(0);
+    let rows: Vec<(String, String)> = entries
+        .iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Value::List(items) if key.eq_ignore_ascii_case("tags") => items
+                    .iter()
+                    .map(|tag| format!("#{}", tag))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                value => value.display(),
+            };
+            let padding = " ".repeat(key_width - display_width(key));
+            (format!("{}{}", key, padding), value)
+        })
+        .collect();
+    let inner_width = rows
+        .iter()
+        .map(|(key, value)| display_width(key) + 2 + display_width(value))
+        .max()
+        .unwrap_or(0)
+        .max(" metadata ".len());
+
+    let mut lines = vec![format!(
+        "{}{}{}",
+        "╭─".bright_black(),
+        " metadata ".bright_black(),
+        format!("{}╮", "─".repeat(inner_width + 1 - " metadata ".len())).bright_black()
+    )];
+    for (key, value) in rows {
+        let padding = inner_width - display_width(&key) - 2 - display_width(&value);
+        let value = if key.trim_end().eq_ignore_ascii_case("title") {
+            value.bold().to_string()
+        } else if key.trim_end().eq_ignore_ascii_case("tags") {
+            value.magenta().to_string()
+        } else {
+            value
+        };
+        lines.push(format!(
+            "{} {}  {}{} {}",
+            "│".bright_black(),
+            key.cyan(),
+            value,
+            " ".repeat(padding),
+            "│".bright_black()
+        ));
+    }
+    lines.push(
+        format!("╰{}╯", "─".repeat(inner_width + 2))
+            .bright_black()
+            .to_string(),
+    );
+    lines
+}
+
+/// Show the front matter source between its fences, with keys, strings and comments colored
+pub(crate) fn raw_lines(source: &str, format: FrontMatterFormat) -> Vec<String> {
+    let fence = format.fence().bright_black().to_string();
+    let mut lines = vec![fence.clone()];
+    lines.extend(source.lines().map(|line| highlight_line(line, format)));
+    lines.push(fence);
+    lines
+}
+
+fn highlight_line(line: &str, format: FrontMatterFormat) -> String {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+
+    if trimmed.starts_with('#') {
+        return line.bright_black().to_string();
+    }
+    if format == FrontMatterFormat::Toml && trimmed.starts_with('[') {
+        return format!("{}{}", indent, trimmed.yellow());
+    }
+    if let Some(item) = trimmed.strip_prefix("- ") {
+        return format!("{}{} {}", indent, "-".bright_black(), highlight_value(item));
+    }
+
+    match trimmed.split_once(format.separator()) {
+        Some((key, value)) => format!(
+            "{}{}{}{}",
+            indent,
+            key.cyan(),
+            format.separator().to_string().bright_black(),
+            highlight_value(value)
+        ),
+        None => line.to_string(),
+    }
+}
+
+fn highlight_value(value: &str) -> String {
+    let trimmed = value.trim();
+    let leading = &value[..value.len() - value.trim_start().len()];
+    let styled = if trimmed.starts_with(['"', '\'']) {
+        trimmed.green().to_string()
+    } else if trimmed.parse::<f64>().is_ok() || matches!(trimmed, "true" | "false") {
+        trimmed.yellow().to_string()
+    } else {
+        trimmed.to_string()
+    };
+    format!("{}{}", leading, styled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn test_parse_yaml() {
+        let front_matter = FrontMatter::parse(
+            "title: \"Hello\"\nauthor: Jane\ntags:\n  - rust\n  - 'cli'\ncategories: [a, b]\nnested:\n  key: value\n# comment",
+            FrontMatterFormat::Yaml,
+        );
+        assert_eq!(front_matter.title(), Some("Hello"));
+        assert_eq!(
+            front_matter.get("tags"),
+            Some(&Value::List(vec!["rust".to_string(), "cli".to_string()]))
+        );
+        assert_eq!(
+            front_matter.get("categories"),
+            Some(&Value::List(vec!["a".to_string(), "b".to_string()]))
+        );
+        assert_eq!(front_matter.get("nested"), None);
+    }
+
+    #[test]
+    fn test_parse_toml() {
+        let front_matter = FrontMatter::parse(
+            "title = \"Hello\"\ndate = 2024-01-02\ntags = [\"a\", \"b\"]\n\n[extra]\nkey = 1",
+            FrontMatterFormat::Toml,
+        );
+        assert_eq!(front_matter.title(), Some("Hello"));
+        assert_eq!(
+            front_matter.get("date"),
+            Some(&Value::Text("2024-01-02".to_string()))
+        );
+        assert_eq!(front_matter.get("key"), None);
+    }
+
+    #[test]
+    fn test_panel_entries_order() {
+        let front_matter = FrontMatter::parse(
+            "layout: post\ntags: [x]\ndate: 2024-01-02\ntitle: T",
+            FrontMatterFormat::Yaml,
+        );
+        let keys: Vec<&str> = front_matter
+            .panel_entries()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(keys, vec!["title", "date", "tags", "layout"]);
+    }
+
+    #[rstest]
+    #[case("title: Hello", "title")]
+    #[case("  - item", "item")]
+    #[case("# comment", "# comment")]
+    fn test_highlight_line_keeps_text(#[case] line: &str, #[case] expected: &str) {
+        assert!(highlight_line(line, FrontMatterFormat::Yaml).contains(expected));
+    }
+}
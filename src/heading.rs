@@ -0,0 +1,212 @@
+use crate::figlet::{BannerFont, FigletFont};
+use crate::width;
+use std::io::IsTerminal;
+
+/// How h1 and h2 headings are drawn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HeadingStyle {
+    /// Decorative `═`/`─` rules around the heading text
+    #[default]
+    Rules,
+    /// Real large text using the Kitty text sizing protocol (OSC 66)
+    Kitty,
+    /// DEC double-height (`ESC #3`/`ESC #4`) and double-width (`ESC #6`) lines
+    DoubleHeight,
+    /// Big block-letter banners for h1 (and optionally h2) using a bundled FIGlet font
+    Banner { font: BannerFont, include_h2: bool },
+}
+
+impl HeadingStyle {
+    /// Pick the best large heading style supported by the current terminal.
+    ///
+    /// Falls back to [`HeadingStyle::Rules`] when stdout is not a terminal or no text sizing
+    /// support is detected, so sizing escapes never end up in a pipe or file.
+    pub fn detect() -> Self {
+        Self::detect_from(std::io::stdout().is_terminal(), |name| {
+            std::env::var(name).ok()
+        })
+    }
+
+    fn detect_from(is_terminal: bool, var: impl Fn(&str) -> Option<String>) -> Self {
+        if !is_terminal {
+            return HeadingStyle::Rules;
+        }
+
+        let term = var("TERM").unwrap_or_default();
+        let term_program = var("TERM_PROGRAM").unwrap_or_default();
+
+        if term == "xterm-kitty" || var("KITTY_WINDOW_ID").is_some() {
+            HeadingStyle::Kitty
+        } else if var("XTERM_VERSION").is_some()
+            || var("KONSOLE_VERSION").is_some()
+            || var("WT_SESSION").is_some()
+            || term_program == "mintty"
+        {
+            HeadingStyle::DoubleHeight
+        } else {
+            HeadingStyle::Rules
+        }
+    }
+}
+
+/// Build the terminal lines that display a heading at a larger size.
+///
+/// Returns `None` when the style does not size headings of this depth, when the text
+/// contains escape sequences (such as OSC 8 links) that cannot be nested in sized text,
+/// or when a banner does not fit in `width` columns.
+pub(crate) fn large_heading_lines(
+    style: HeadingStyle,
+    depth: u8,
+    symbol: &str,
+    text: &str,
+    width: usize,
+) -> Option<Vec<String>> {
+    if depth > 2 || text.contains('\x1b') {
+        return None;
+    }
+
+    let text = match style {
+        HeadingStyle::Banner { .. } => text.to_string(),
+        _ => format!("{} {}", symbol, text),
+    };
+
+    match style {
+        HeadingStyle::Rules => None,
+        HeadingStyle::Banner { font, include_h2 } => {
+            if depth == 2 && !include_h2 {
+                return None;
+            }
+            FigletFont::bundled(font).render_wrapped(&text, width)
+        }
+        HeadingStyle::Kitty => {
+            // h1 spans three rows, h2 two rows; the cursor stays on the first row
+            let scale = if depth == 1 { 3 } else { 2 };
+            let mut lines = vec![format!("\x1b]66;s={};{}\x07", scale, text)];
+            lines.extend((1..scale).map(|_| String::new()));
+            Some(lines)
+        }
+        HeadingStyle::DoubleHeight => {
+            // Every character is drawn two columns wide
+            let rows = width::wrap(&text, width / 2);
+            if depth == 1 {
+                Some(
+                    rows.iter()
+                        .flat_map(|row| [format!("\x1b#3{}", row), format!("\x1b#4{}", row)])
+                        .collect(),
+                )
+            } else {
+                Some(rows.iter().map(|row| format!("\x1b#6{}", row)).collect())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use std::collections::HashMap;
+
+    #[rstest]
+    #[case(&[("TERM", "xterm-kitty")], HeadingStyle::Kitty)]
+    #[case(&[("KITTY_WINDOW_ID", "1")], HeadingStyle::Kitty)]
+    #[case(&[("TERM", "xterm"), ("XTERM_VERSION", "XTerm(390)")], HeadingStyle::DoubleHeight)]
+    #[case(&[("KONSOLE_VERSION", "230800")], HeadingStyle::DoubleHeight)]
+    #[case(&[("TERM", "xterm-256color")], HeadingStyle::Rules)]
+    #[case(&[], HeadingStyle::Rules)]
+    fn test_detect_from(#[case] vars: &[(&str, &str)], #[case] expected: HeadingStyle) {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        let detected =
+            HeadingStyle::detect_from(true, |name| vars.get(name).map(|v| v.to_string()));
+        assert_eq!(detected, expected);
+    }
+
+    #[test]
+    fn test_detect_from_pipe() {
+        let detected = HeadingStyle::detect_from(false, |_| Some("xterm-kitty".to_string()));
+        assert_eq!(detected, HeadingStyle::Rules);
+    }
+
+    #[test]
+    fn test_large_heading_lines_kitty() {
+        let lines = large_heading_lines(HeadingStyle::Kitty, 1, "①", "Title", 80).unwrap();
+        assert_eq!(lines[0], "\x1b]66;s=3;① Title\x07");
+        assert_eq!(lines.len(), 3);
+        let lines = large_heading_lines(HeadingStyle::Kitty, 2, "②", "Title", 80).unwrap();
+        assert_eq!(lines.len(), 2);
+    }
+
+    #[test]
+    fn test_large_heading_lines_double_height() {
+        let lines = large_heading_lines(HeadingStyle::DoubleHeight, 1, "①", "Title", 80).unwrap();
+        assert_eq!(lines, vec!["\x1b#3① Title", "\x1b#4① Title"]);
+        let lines = large_heading_lines(HeadingStyle::DoubleHeight, 2, "②", "Title", 80).unwrap();
+        assert_eq!(lines, vec!["\x1b#6② Title"]);
+    }
+
+    #[test]
+    fn test_large_heading_lines_double_height_wraps() {
+        // 20 columns hold 10 double-width characters per row
+        let lines =
+            large_heading_lines(HeadingStyle::DoubleHeight, 1, "①", "Getting started", 20).unwrap();
+        assert_eq!(
+            lines,
+            vec![
+                "\x1b#3① Getting",
+                "\x1b#4① Getting",
+                "\x1b#3started",
+                "\x1b#4started"
+            ]
+        );
+        let lines =
+            large_heading_lines(HeadingStyle::DoubleHeight, 2, "②", "Getting started", 20).unwrap();
+        assert_eq!(lines, vec!["\x1b#6② Getting", "\x1b#6started"]);
+    }
+
+    #[rstest]
+    #[case(HeadingStyle::Rules, 1, "Title")]
+    #[case(HeadingStyle::Kitty, 3, "Title")]
+    #[case(
+        HeadingStyle::Kitty,
+        1,
+        "\x1b]8;;https://example.com\x1b\\Link\x1b]8;;\x1b\\"
+    )]
+    fn test_large_heading_lines_fallback(
+        #[case] style: HeadingStyle,
+        #[case] depth: u8,
+        #[case] text: &str,
+    ) {
+        assert!(large_heading_lines(style, depth, "①", text, 80).is_none());
+    }
+
+    #[test]
+    fn test_large_heading_lines_banner() {
+        let style = HeadingStyle::Banner {
+            font: BannerFont::HalfBlock,
+            include_h2: false,
+        };
+        let lines = large_heading_lines(style, 1, "①", "Hi", 80).unwrap();
+        assert_eq!(lines.len(), 3);
+        assert!(!lines.concat().contains('①'));
+        // h2 banners are opt-in
+        assert!(large_heading_lines(style, 2, "②", "Hi", 80).is_none());
+        let style = HeadingStyle::Banner {
+            font: BannerFont::HalfBlock,
+            include_h2: true,
+        };
+        assert!(large_heading_lines(style, 2, "②", "Hi", 80).is_some());
+    }
+
+    #[test]
+    fn test_large_heading_lines_banner_wraps_or_falls_back() {
+        let style = HeadingStyle::Banner {
+            font: BannerFont::Block,
+            include_h2: false,
+        };
+        // Two words that only fit on separate banners
+        let lines = large_heading_lines(style, 1, "①", "HELLO WORLD", 24).unwrap();
+        assert_eq!(lines.len(), 10);
+        // A single word wider than the layout falls back to the regular style
+        assert!(large_heading_lines(style, 1, "①", "INTERNATIONALIZATION", 24).is_none());
+    }
+}
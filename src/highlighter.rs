@@ -1,5 +1,24 @@
+use std::ops::Range;
+use tree_sitter::{Language, Parser};
 use tree_sitter_highlight::{Highlight, HighlightConfiguration, HighlightEvent, Highlighter};
 
+/// Red underline used to mark syntax errors
+const ERROR_STYLE: &str = "\x1b[4;31m";
+const RESET: &str = "\x1b[0m";
+
+/// A syntax error reported by tree-sitter for a code snippet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    /// Byte range of the offending source, never empty for non-empty code
+    pub range: Range<usize>,
+    /// 1-based line number within the snippet
+    pub line: usize,
+    /// 1-based column number within the snippet
+    pub column: usize,
+    /// Human-readable description of the error
+    pub message: String,
+}
+
 /// Syntax highlighter supporting various programming languages and HTML.
 ///
 /// This struct uses tree-sitter to provide syntax highlighting with ANSI color codes
@@ -26,9 +45,9 @@ impl SyntaxHighlighter {
         }
     }
 
-    /// Get the appropriate tree-sitter language and highlight configuration for a given language
-    fn get_highlight_config(lang: &str) -> Option<HighlightConfiguration> {
-        let (language, query) = match lang.to_lowercase().as_str() {
+    /// Get the tree-sitter language and highlights query for a given language
+    fn get_language(lang: &str) -> Option<(Language, &'static str)> {
+        let language = match lang.to_lowercase().as_str() {
             "rust" | "rs" => (
                 tree_sitter_rust::LANGUAGE.into(),
                 tree_sitter_rust::HIGHLIGHTS_QUERY,
@@ -95,7 +114,12 @@ impl SyntaxHighlighter {
             ),
             _ => return None,
         };
+        Some(language)
+    }
 
+    /// Get the highlight configuration for a given language
+    fn get_highlight_config(lang: &str) -> Option<HighlightConfiguration> {
+        let (language, query) = Self::get_language(lang)?;
         let mut config = HighlightConfiguration::new(language, "", query, "", "").ok()?;
 
         config.configure(&[
@@ -130,8 +154,109 @@ impl SyntaxHighlighter {
         Some(config)
     }
 
+    /// Returns true if the language can be highlighted
+    pub fn is_supported(lang: &str) -> bool {
+        Self::get_language(lang).is_some()
+    }
+
+    /// Map a file extension to a language name understood by [`SyntaxHighlighter::highlight`]
+    pub fn language_for_extension(ext: &str) -> Option<&'static str> {
+        let lang = match ext.to_lowercase().as_str() {
+            "rs" => "rust",
+            "js" | "mjs" | "cjs" | "jsx" => "javascript",
+            "ts" | "mts" | "cts" => "typescript",
+            "tsx" => "tsx",
+            "py" => "python",
+            "go" => "go",
+            "html" | "htm" => "html",
+            "css" => "css",
+            "json" => "json",
+            "sh" | "bash" => "bash",
+            "c" | "h" => "c",
+            "cpp" | "cc" | "cxx" | "hpp" | "hh" => "cpp",
+            "java" => "java",
+            "hs" => "haskell",
+            "elm" => "elm",
+            "mq" => "mq",
+            _ => return None,
+        };
+        Some(lang)
+    }
+
     /// Highlight code and return colored output
     pub fn highlight(&mut self, code: &str, lang: Option<&str>) -> String {
+        self.highlight_ranges(code, lang, &[])
+    }
+
+    /// Highlight code and underline any syntax errors found by tree-sitter
+    pub fn highlight_with_errors(&mut self, code: &str, lang: Option<&str>) -> String {
+        let errors = lang
+            .and_then(|lang| Self::syntax_errors(code, lang))
+            .unwrap_or_default();
+        let ranges: Vec<Range<usize>> = errors.into_iter().map(|e| e.range).collect();
+        self.highlight_ranges(code, lang, &ranges)
+    }
+
+    /// Parse code with tree-sitter and collect its ERROR and MISSING nodes.
+    ///
+    /// Returns `None` if the language is not supported.
+    pub fn syntax_errors(code: &str, lang: &str) -> Option<Vec<SyntaxError>> {
+        let (language, _) = Self::get_language(lang)?;
+        let mut parser = Parser::new();
+        parser.set_language(&language).ok()?;
+        let tree = parser.parse(code, None)?;
+
+        let mut errors = Vec::new();
+        let mut stack = vec![tree.root_node()];
+        while let Some(node) = stack.pop() {
+            if node.is_error() || node.is_missing() {
+                let range = Self::visible_range(code, node.start_byte()..node.end_byte());
+                let message = if node.is_missing() {
+                    format!("missing `{}`", node.kind())
+                } else {
+                    let text = code[range.clone()].lines().next().unwrap_or_default();
+                    let text: String = text.chars().take(20).collect();
+                    format!("unexpected `{}`", text.trim())
+                };
+                errors.push(SyntaxError {
+                    range,
+                    line: node.start_position().row + 1,
+                    column: node.start_position().column + 1,
+                    message,
+                });
+            } else if node.has_error() {
+                for i in (0..node.child_count()).rev() {
+                    if let Some(child) = node.child(i) {
+                        stack.push(child);
+                    }
+                }
+            }
+        }
+
+        errors.sort_by_key(|e| e.range.start);
+        Some(errors)
+    }
+
+    /// Widen an empty range (MISSING nodes) to one character so it can be displayed
+    fn visible_range(code: &str, range: Range<usize>) -> Range<usize> {
+        if !range.is_empty() || code.is_empty() {
+            return range;
+        }
+        match code[..range.start].char_indices().next_back() {
+            Some((start, _)) => start..range.start,
+            None => {
+                let len = code.chars().next().map(char::len_utf8).unwrap_or(0);
+                range.start..range.start + len
+            }
+        }
+    }
+
+    fn highlight_ranges(
+        &mut self,
+        code: &str,
+        lang: Option<&str>,
+        errors: &[Range<usize>],
+    ) -> String {
         // If no language specified or config not available, return plain text
         let Some(lang) = lang else {
             return code.to_string();
@@ -151,25 +276,28 @@ impl SyntaxHighlighter {
 
         let mut result = String::new();
         let mut current_pos = 0;
+        let mut current_color = None;
 
         for event in highlights {
             match event {
                 Ok(HighlightEvent::Source { start, end }) => {
                     if start > current_pos {
                         // Add unhighlighted text
-                        result.push_str(&code[current_pos..start]);
+                        Self::push_source(&mut result, code, current_pos..start, errors, None);
                     }
-                    result.push_str(&code[start..end]);
+                    Self::push_source(&mut result, code, start..end, errors, current_color);
                     current_pos = end;
                 }
                 Ok(HighlightEvent::HighlightStart(Highlight(idx))) => {
                     // Apply color based on highlight type
                     let color_code = Self::get_color_for_highlight(idx);
                     result.push_str(color_code);
+                    current_color = Some(color_code);
                 }
                 Ok(HighlightEvent::HighlightEnd) => {
                     // Reset color
-                    result.push_str("\x1b[0m");
+                    result.push_str(RESET);
+                    current_color = None;
                 }
                 Err(_) => {}
             }
@@ -177,12 +305,49 @@ impl SyntaxHighlighter {
 
         // Add any remaining text
         if current_pos < code.len() {
-            result.push_str(&code[current_pos..]);
+            Self::push_source(&mut result, code, current_pos..code.len(), errors, None);
         }
 
         result
     }
 
+    /// Append a slice of source, underlining the parts that overlap an error range
+    fn push_source(
+        result: &mut String,
+        code: &str,
+        range: Range<usize>,
+        errors: &[Range<usize>],
+        color: Option<&str>,
+    ) {
+        let mut pos = range.start;
+        while pos < range.end {
+            let error = errors.iter().find(|e| e.start <= pos && pos < e.end);
+            let next = match error {
+                Some(e) => e.end.min(range.end),
+                None => errors
+                    .iter()
+                    .filter(|e| e.start > pos)
+                    .map(|e| e.start)
+                    .min()
+                    .unwrap_or(range.end)
+                    .min(range.end),
+            };
+
+            if error.is_some() {
+                result.push_str(ERROR_STYLE);
+                result.push_str(&code[pos..next]);
+                result.push_str(RESET);
+                // Restore the token color for the rest of the highlight span
+                if let Some(color) = color {
+                    result.push_str(color);
+                }
+            } else {
+                result.push_str(&code[pos..next]);
+            }
+            pos = next;
+        }
+    }
+
     /// Map highlight index to ANSI color codes
     fn get_color_for_highlight(idx: usize) -> &'static str {
         match idx {
@@ -271,6 +436,47 @@ mod tests {
         );
     }
 
+    #[rstest]
+    #[case("rs", Some("rust"))]
+    #[case("TSX", Some("tsx"))]
+    #[case("hpp", Some("cpp"))]
+    #[case("txt", None)]
+    fn test_language_for_extension(#[case] ext: &str, #[case] expected: Option<&str>) {
+        assert_eq!(SyntaxHighlighter::language_for_extension(ext), expected);
+    }
+
+    #[test]
+    fn test_syntax_errors_valid_code() {
+        let errors = SyntaxHighlighter::syntax_errors("fn main() {}", "rust").unwrap();
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_syntax_errors_invalid_code() {
+        let errors =
+            SyntaxHighlighter::syntax_errors("fn main() {\n    let x = ;\n}", "rust").unwrap();
+        assert!(!errors.is_empty());
+        assert_eq!(errors[0].line, 2);
+        assert!(errors.iter().all(|e| !e.range.is_empty()));
+    }
+
+    #[test]
+    fn test_syntax_errors_unsupported_language() {
+        assert!(SyntaxHighlighter::syntax_errors("whatever", "unknown").is_none());
+    }
+
+    #[test]
+    fn test_highlight_with_errors_marks_error() {
+        let mut highlighter = SyntaxHighlighter::new();
+        let result = highlighter.highlight_with_errors("fn main( {}", Some("rust"));
+        assert!(result.contains(ERROR_STYLE));
+        assert!(
+            !highlighter
+                .highlight("fn main( {}", Some("rust"))
+                .contains(ERROR_STYLE)
+        );
+    }
+
     #[test]
     fn test_highlighting_empty_code() {
         let mut highlighter = SyntaxHighlighter::new();
@@ -0,0 +1,687 @@
+use crate::inline_style;
+use crate::renderer::make_clickable_link;
+use colored::*;
+use mq_markdown::Node;
+
+/// Elements the interpreter understands; HTML using any other element is highlighted as source
+const KNOWN_ELEMENTS: &[&str] = &[
+    "a", "abbr", "b", "br", "center", "code", "del", "details", "div", "em", "h1", "h2", "h3",
+    "h4", "h5", "h6", "hr", "i", "img", "ins", "kbd", "mark", "p", "picture", "s", "samp", "small",
+    "source", "span", "strike", "strong", "sub", "summary", "sup", "tt", "u", "var",
+];
+
+/// Elements that never have content or a closing tag
+const VOID_ELEMENTS: &[&str] = &["br", "hr", "img", "source", "wbr"];
+
+/// Elements that start a new line
+const BLOCK_ELEMENTS: &[&str] = &["center", "div", "p", "picture"];
+
+/// A single start or end tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Tag {
+    pub(crate) name: String,
+    attributes: Vec<(String, String)>,
+    pub(crate) closing: bool,
+    self_closing: bool,
+}
+
+impl Tag {
+    pub(crate) fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub(crate) fn is_void(&self) -> bool {
+        self.self_closing || VOID_ELEMENTS.contains(&self.name.as_str())
+    }
+
+    fn is_centered(&self) -> bool {
+        self.name == "center"
+            || self
+                .attribute("align")
+                .is_some_and(|align| align.eq_ignore_ascii_case("center"))
+    }
+}
+
+/// Parse a single tag such as `<img src="a.png" width=100>` or `</kbd>`
+pub(crate) fn parse_tag(source: &str) -> Option<Tag> {
+    let inner = source.trim().strip_prefix('<')?.strip_suffix('>')?;
+    let (closing, inner) = match inner.strip_prefix('/') {
+        Some(rest) => (true, rest),
+        None => (false, inner),
+    };
+    let (self_closing, inner) = match inner.strip_suffix('/') {
+        Some(rest) => (true, rest),
+        None => (false, inner),
+    };
+
+    let name_end = inner
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(inner.len());
+    let name = &inner[..name_end];
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    Some(Tag {
+        name: name.to_ascii_lowercase(),
+        attributes: parse_attributes(&inner[name_end..]),
+        closing,
+        self_closing,
+    })
+}
+
+fn parse_attributes(source: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    let mut rest = source.trim_start();
+
+    while !rest.is_empty() {
+        let name_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(rest.len());
+        let name = rest[..name_end].to_string();
+        rest = rest[name_end..].trim_start();
+
+        let value = if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            let (value, remaining) = match after.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let end = after[1..].find(quote).map_or(after.len(), |e| e + 1);
+                    (&after[1..end], after.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                    (&after[..end], &after[end..])
+                }
+            };
+            rest = remaining.trim_start();
+            value.to_string()
+        } else {
+            String::new()
+        };
+
+        if name.is_empty() {
+            break;
+        }
+        attributes.push((name, value));
+    }
+    attributes
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Tag(Tag),
+    Text(String),
+}
+
+fn tokenize(html: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        if rest.starts_with('<')
+            && let Some(end) = rest.find('>')
+            && let Some(tag) = parse_tag(&rest[..=end])
+        {
+            tokens.push(Token::Tag(tag));
+            rest = &rest[end + 1..];
+            continue;
+        }
+
+        let first = rest.chars().next().map_or(1, char::len_utf8);
+        let end = rest[first..].find('<').map_or(rest.len(), |e| e + first);
+        tokens.push(Token::Text(rest[..end].to_string()));
+        rest = &rest[end..];
+    }
+    tokens
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HtmlNode {
+    Element { tag: Tag, children: Vec<HtmlNode> },
+    Text(String),
+}
+
+/// Build an element tree, or `None` if the HTML uses an element outside [`KNOWN_ELEMENTS`]
+fn parse_tree(html: &str) -> Option<Vec<HtmlNode>> {
+    let mut stack: Vec<(Tag, Vec<HtmlNode>)> = Vec::new();
+    let mut root = Vec::new();
+
+    fn close(stack: &mut Vec<(Tag, Vec<HtmlNode>)>, root: &mut Vec<HtmlNode>) {
+        if let Some((tag, children)) = stack.pop() {
+            let element = HtmlNode::Element { tag, children };
+            match stack.last_mut() {
+                Some((_, siblings)) => siblings.push(element),
+                None => root.push(element),
+            }
+        }
+    }
+
+    for token in tokenize(html) {
+        match token {
+            Token::Text(text) => match stack.last_mut() {
+                Some((_, children)) => children.push(HtmlNode::Text(text)),
+                None => root.push(HtmlNode::Text(text)),
+            },
+            Token::Tag(tag) if !KNOWN_ELEMENTS.contains(&tag.name.as_str()) => return None,
+            Token::Tag(tag) if tag.closing => {
+                // Close up to the matching element; stray end tags are ignored
+                if let Some(index) = stack.iter().rposition(|(open, _)| open.name == tag.name) {
+                    while stack.len() > index {
+                        close(&mut stack, &mut root);
+                    }
+                }
+            }
+            Token::Tag(tag) if tag.is_void() => {
+                let element = HtmlNode::Element {
+                    tag,
+                    children: Vec::new(),
+                };
+                match stack.last_mut() {
+                    Some((_, children)) => children.push(element),
+                    None => root.push(element),
+                }
+            }
+            Token::Tag(tag) => stack.push((tag, Vec::new())),
+        }
+    }
+    while !stack.is_empty() {
+        close(&mut stack, &mut root);
+    }
+    Some(root)
+}
+
+/// Terminal output of a block of HTML
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum HtmlBlock {
+    Line { text: String, centered: bool },
+    Image { src: String, alt: String },
+    Heading { depth: u8, text: String },
+    Rule,
+}
+
+/// Interpret a block of HTML, or return `None` if it uses elements the interpreter does not know.
+///
+/// `<details>` sections show only their summary unless they are `open` or `expand_details` is set.
+pub(crate) fn interpret_block(html: &str, expand_details: bool) -> Option<Vec<HtmlBlock>> {
+    let tree = parse_tree(html)?;
+    let mut interpreter = Interpreter {
+        expand_details,
+        ..Default::default()
+    };
+    interpreter.walk(&tree, false);
+    interpreter.flush(false);
+    Some(interpreter.blocks)
+}
+
+/// An HTML block that opens a `<details>` section whose body is the markdown that follows it,
+/// up to a matching `</details>` block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DetailsStart {
+    pub(crate) summary: String,
+    pub(crate) open: bool,
+    /// Any HTML after the summary in the opening block
+    pub(crate) body: Vec<HtmlBlock>,
+}
+
+/// Recognize a block such as `<details>\n<summary>More</summary>` that leaves `<details>` open
+pub(crate) fn details_start(html: &str) -> Option<DetailsStart> {
+    let closes_details = tokenize(html)
+        .iter()
+        .any(|token| matches!(token, Token::Tag(tag) if tag.closing && tag.name == "details"));
+    if closes_details {
+        return None;
+    }
+
+    let tree = parse_tree(html)?;
+    let mut elements = tree
+        .iter()
+        .filter(|node| !matches!(node, HtmlNode::Text(text) if text.trim().is_empty()));
+    let (Some(HtmlNode::Element { tag, children }), None) = (elements.next(), elements.next())
+    else {
+        return None;
+    };
+    if tag.name != "details" {
+        return None;
+    }
+
+    let (summary, rest) = split_summary(children);
+    let mut interpreter = Interpreter::default();
+    interpreter.walk(&rest, false);
+    interpreter.flush(false);
+    Some(DetailsStart {
+        summary,
+        open: tag.attribute("open").is_some(),
+        body: interpreter.blocks,
+    })
+}
+
+/// Whether a block only closes a `<details>` section
+pub(crate) fn is_details_end(html: &str) -> bool {
+    let tokens = tokenize(html);
+    tokens
+        .iter()
+        .any(|token| matches!(token, Token::Tag(tag) if tag.closing && tag.name == "details"))
+        && tokens.iter().all(|token| match token {
+            Token::Tag(tag) => tag.closing && tag.name == "details",
+            Token::Text(text) => text.trim().is_empty(),
+        })
+}
+
+/// The `▶ summary` line of a collapsed section, or `▼ summary` once expanded
+pub(crate) fn summary_line(summary: &str, expanded: bool) -> String {
+    let marker = if expanded { "▼" } else { "▶" };
+    format!("{} {}", marker.cyan(), summary.bold())
+}
+
+/// Separate the `<summary>` text of a details element from the rest of its content.
+/// Browsers show "Details" when there is no summary.
+fn split_summary(children: &[HtmlNode]) -> (String, Vec<HtmlNode>) {
+    let mut summary = None;
+    let mut rest = Vec::new();
+    for child in children {
+        match child {
+            HtmlNode::Element { tag, children } if tag.name == "summary" && summary.is_none() => {
+                summary = Some(inline_text(children).trim().to_string());
+            }
+            child => rest.push(child.clone()),
+        }
+    }
+    (summary.unwrap_or_else(|| "Details".to_string()), rest)
+}
+
+/// Interpret inline HTML as a single line of styled text
+pub(crate) fn interpret_inline(html: &str) -> Option<String> {
+    Some(inline_text(&parse_tree(html)?))
+}
+
+#[derive(Default)]
+struct Interpreter {
+    blocks: Vec<HtmlBlock>,
+    line: String,
+    expand_details: bool,
+}
+
+impl Interpreter {
+    fn flush(&mut self, centered: bool) {
+        let text = self.line.trim().to_string();
+        self.line.clear();
+        if !text.is_empty() {
+            self.blocks.push(HtmlBlock::Line { text, centered });
+        }
+    }
+
+    fn walk(&mut self, nodes: &[HtmlNode], centered: bool) {
+        for node in nodes {
+            match node {
+                HtmlNode::Text(text) => self.line.push_str(&collapse_whitespace(text)),
+                HtmlNode::Element { tag, children } => match tag.name.as_str() {
+                    "br" => {
+                        // Consecutive breaks leave a blank line
+                        if self.line.trim().is_empty()
+                            && matches!(self.blocks.last(), Some(HtmlBlock::Line { .. }))
+                        {
+                            self.blocks.push(HtmlBlock::Line {
+                                text: String::new(),
+                                centered,
+                            });
+                        }
+                        self.flush(centered);
+                    }
+                    "hr" => {
+                        self.flush(centered);
+                        self.blocks.push(HtmlBlock::Rule);
+                    }
+                    "img" => {
+                        self.flush(centered);
+                        self.blocks.push(HtmlBlock::Image {
+                            src: tag.attribute("src").unwrap_or_default().to_string(),
+                            alt: decode_entities(tag.attribute("alt").unwrap_or_default()),
+                        });
+                    }
+                    "source" => {}
+                    name if name.len() == 2 && name.starts_with('h') => {
+                        self.flush(centered);
+                        self.blocks.push(HtmlBlock::Heading {
+                            depth: name[1..].parse().unwrap_or(1),
+                            text: inline_text(children).trim().to_string(),
+                        });
+                    }
+                    "details" => {
+                        self.flush(centered);
+                        let (summary, rest) = split_summary(children);
+                        let expanded = self.expand_details || tag.attribute("open").is_some();
+                        self.blocks.push(HtmlBlock::Line {
+                            text: summary_line(&summary, expanded),
+                            centered,
+                        });
+                        if expanded {
+                            self.walk(&rest, centered);
+                            self.flush(centered);
+                        }
+                    }
+                    name if BLOCK_ELEMENTS.contains(&name) => {
+                        let centered = centered || tag.is_centered();
+                        self.flush(centered);
+                        self.walk(children, centered);
+                        self.flush(centered);
+                    }
+                    _ => self.line.push_str(&inline_element(tag, children)),
+                },
+            }
+        }
+    }
+}
+
+fn inline_text(nodes: &[HtmlNode]) -> String {
+    let mut text = String::new();
+    for node in nodes {
+        match node {
+            HtmlNode::Text(value) => text.push_str(&collapse_whitespace(value)),
+            HtmlNode::Element { tag, children } => text.push_str(&inline_element(tag, children)),
+        }
+    }
+    text
+}
+
+fn inline_element(tag: &Tag, children: &[HtmlNode]) -> String {
+    match tag.name.as_str() {
+        "br" => " ".to_string(),
+        "img" => {
+            let alt = decode_entities(tag.attribute("alt").unwrap_or_default());
+            let src = tag.attribute("src").unwrap_or_default();
+            format!("🖼️ {}", if alt.is_empty() { src } else { &alt })
+        }
+        "a" => {
+            let text = inline_text(children);
+            match tag.attribute("href") {
+                Some(href) => make_clickable_link(href, text.trim())
+                    .underline()
+                    .bright_blue()
+                    .to_string(),
+                None => text,
+            }
+        }
+        _ => {
+            let inner = inline_text(children);
+            style_inline(tag, &inner).unwrap_or(inner)
+        }
+    }
+}
+
+/// Apply the terminal equivalent of an inline formatting element to its rendered content
+pub(crate) fn style_inline(tag: &Tag, inner: &str) -> Option<String> {
+    let styled = match tag.name.as_str() {
+        "kbd" => key_cap(inner),
+        "sub" => inline_style::subscript(inner),
+        "sup" => inline_style::superscript(inner),
+        "b" | "strong" => inner.bold().to_string(),
+        "i" | "em" | "var" => inner.italic().to_string(),
+        "u" | "ins" | "abbr" => inner.underline().to_string(),
+        "s" | "del" | "strike" => inline_style::strikethrough(inner),
+        "mark" => inline_style::highlight(inner),
+        "code" | "tt" | "samp" => format!("`{}`", inner).bright_yellow().to_string(),
+        "small" => inner.dimmed().to_string(),
+        "span" => inner.to_string(),
+        _ => return None,
+    };
+    Some(styled)
+}
+
+/// Draw a keyboard key such as `<kbd>Ctrl</kbd>` as a key cap
+pub(crate) fn key_cap(key: &str) -> String {
+    format!("⟦{}⟧", key.trim())
+        .bold()
+        .on_bright_black()
+        .to_string()
+}
+
+/// Index of the sibling `Html` node that closes the element opened at `start`
+pub(crate) fn find_closing(nodes: &[Node], start: usize, name: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, node) in nodes.iter().enumerate().skip(start + 1) {
+        if let Node::Html(html) = node
+            && let Some(tag) = parse_tag(&html.value)
+            && tag.name == name
+        {
+            if !tag.closing {
+                depth += 1;
+            } else if depth == 0 {
+                return Some(i);
+            } else {
+                depth -= 1;
+            }
+        }
+    }
+    None
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut last_space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !last_space {
+                collapsed.push(' ');
+            }
+            last_space = true;
+        } else {
+            collapsed.push(c);
+            last_space = false;
+        }
+    }
+    decode_entities(&collapsed)
+}
+
+/// Decode the named entities common in READMEs and numeric character references
+pub(crate) fn decode_entities(text: &str) -> String {
+    const ENTITIES: &[(&str, &str)] = &[
+        ("amp", "&"),
+        ("lt", "<"),
+        ("gt", ">"),
+        ("quot", "\""),
+        ("apos", "'"),
+        ("nbsp", "\u{a0}"),
+        ("copy", "©"),
+        ("reg", "®"),
+        ("trade", "™"),
+        ("mdash", "—"),
+        ("ndash", "–"),
+        ("hellip", "…"),
+        ("middot", "·"),
+        ("bull", "•"),
+        ("rarr", "→"),
+        ("larr", "←"),
+    ];
+
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| {
+                let name = &rest[1..=end];
+                let value = match name.strip_prefix('#') {
+                    Some(number) => match number.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                        None => number.parse().ok(),
+                    }
+                    .and_then(char::from_u32)
+                    .map(String::from),
+                    None => ENTITIES
+                        .iter()
+                        .find(|(n, _)| *n == name)
+                        .map(|(_, v)| v.to_string()),
+                };
+                value.map(|value| (value, end + 2))
+            });
+        match entity {
+            Some((value, len)) => {
+                decoded.push_str(&value);
+                rest = &rest[len..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn test_parse_tag() {
+        let tag = parse_tag("<img src=\"logo.png\" width=100 alt='Logo' hidden>").unwrap();
+        assert_eq!(tag.name, "img");
+        assert_eq!(tag.attribute("src"), Some("logo.png"));
+        assert_eq!(tag.attribute("width"), Some("100"));
+        assert_eq!(tag.attribute("ALT"), Some("Logo"));
+        assert_eq!(tag.attribute("hidden"), Some(""));
+        assert!(tag.is_void());
+
+        let tag = parse_tag("</KBD>").unwrap();
+        assert_eq!(tag.name, "kbd");
+        assert!(tag.closing);
+        assert!(parse_tag("<br/>").unwrap().is_void());
+        assert!(parse_tag("< not a tag>").is_none());
+    }
+
+    #[rstest]
+    #[case("Tom &amp; Jerry", "Tom & Jerry")]
+    #[case("&lt;tag&gt; &#169; &#x2014;", "<tag> © —")]
+    #[case("R&D; &unknown;", "R&D; &unknown;")]
+    fn test_decode_entities(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(decode_entities(text), expected);
+    }
+
+    #[test]
+    fn test_interpret_block_centered_paragraph() {
+        let blocks = interpret_block(
+            "<p align=\"center\">\n  <img src=\"logo.png\" width=\"200\">\n  <br>\n  A <b>fast</b> viewer\n</p>",
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            blocks[0],
+            HtmlBlock::Image {
+                src: "logo.png".to_string(),
+                alt: String::new()
+            }
+        );
+        let HtmlBlock::Line { text, centered } = &blocks[1] else {
+            panic!("expected a line, got {:?}", blocks[1]);
+        };
+        assert!(centered);
+        assert!(text.contains("fast"));
+        assert!(!text.contains("<b>"));
+    }
+
+    #[test]
+    fn test_interpret_block_heading_and_rule() {
+        let blocks = interpret_block("<h2>Title</h2><hr>", false).unwrap();
+        assert_eq!(
+            blocks,
+            vec![
+                HtmlBlock::Heading {
+                    depth: 2,
+                    text: "Title".to_string()
+                },
+                HtmlBlock::Rule
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case("<details><summary>More</summary>Hidden text</details>", false, "▶", 1)]
+    #[case("<details><summary>More</summary>Hidden text</details>", true, "▼", 2)]
+    #[case(
+        "<details open><summary>More</summary>Hidden text</details>",
+        false,
+        "▼",
+        2
+    )]
+    fn test_interpret_block_details(
+        #[case] html: &str,
+        #[case] expand_details: bool,
+        #[case] marker: &str,
+        #[case] expected_len: usize,
+    ) {
+        let blocks = interpret_block(html, expand_details).unwrap();
+        assert_eq!(blocks.len(), expected_len);
+        assert!(
+            matches!(&blocks[0], HtmlBlock::Line { text, .. } if text.contains(marker) && text.contains("More"))
+        );
+    }
+
+    #[test]
+    fn test_details_start() {
+        let details = details_start("<details open>\n<summary><b>Install</b></summary>").unwrap();
+        assert!(details.summary.contains("Install"));
+        assert!(details.open);
+        assert!(details.body.is_empty());
+
+        assert_eq!(details_start("<details>").unwrap().summary, "Details");
+        assert!(details_start("<details><summary>x</summary>y</details>").is_none());
+        assert!(details_start("<p>x</p>").is_none());
+    }
+
+    #[rstest]
+    #[case("</details>", true)]
+    #[case("  </details>\n", true)]
+    #[case("</details>\n<p>x</p>", false)]
+    #[case("</div>", false)]
+    fn test_is_details_end(#[case] html: &str, #[case] expected: bool) {
+        assert_eq!(is_details_end(html), expected);
+    }
+
+    #[rstest]
+    #[case("<table><tr><td>x</td></tr></table>")]
+    #[case("<script>alert(1)</script>")]
+    fn test_interpret_block_unknown_elements(#[case] html: &str) {
+        assert!(interpret_block(html, false).is_none());
+    }
+
+    #[test]
+    fn test_interpret_inline() {
+        let text = interpret_inline(
+            "<a href=\"https://example.com\"><img src=\"badge.svg\" alt=\"CI\"></a>",
+        )
+        .unwrap();
+        assert!(text.contains("https://example.com"));
+        assert!(text.contains("CI"));
+        assert!(interpret_inline("<!-- comment -->").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_closing() {
+        let markdown: mq_markdown::Markdown =
+            "Press <kbd>Ctrl</kbd> + <kbd>C</kbd>".parse().unwrap();
+        let Some(Node::Fragment(fragment)) = markdown.nodes.first() else {
+            panic!("expected a paragraph");
+        };
+        let start = fragment
+            .values
+            .iter()
+            .position(|node| matches!(node, Node::Html(_)))
+            .unwrap();
+        assert_eq!(
+            find_closing(&fragment.values, start, "kbd"),
+            Some(start + 2)
+        );
+    }
+}
@@ -0,0 +1,305 @@
+use colored::*;
+use mq_markdown::{Delete, Node};
+
+const SUPERSCRIPT_CHARS: &[(char, char)] = &[
+    ('0', '⁰'),
+    ('1', '¹'),
+    ('2', '²'),
+    ('3', '³'),
+    ('4', '⁴'),
+    ('5', '⁵'),
+    ('6', '⁶'),
+    ('7', '⁷'),
+    ('8', '⁸'),
+    ('9', '⁹'),
+    ('+', '⁺'),
+    ('-', '⁻'),
+    ('=', '⁼'),
+    ('(', '⁽'),
+    (')', '⁾'),
+    ('a', 'ᵃ'),
+    ('b', 'ᵇ'),
+    ('c', 'ᶜ'),
+    ('d', 'ᵈ'),
+    ('e', 'ᵉ'),
+    ('f', 'ᶠ'),
+    ('g', 'ᵍ'),
+    ('h', 'ʰ'),
+    ('i', 'ⁱ'),
+    ('j', 'ʲ'),
+    ('k', 'ᵏ'),
+    ('l', 'ˡ'),
+    ('m', 'ᵐ'),
+    ('n', 'ⁿ'),
+    ('o', 'ᵒ'),
+    ('p', 'ᵖ'),
+    ('r', 'ʳ'),
+    ('s', 'ˢ'),
+    ('t', 'ᵗ'),
+    ('u', 'ᵘ'),
+    ('v', 'ᵛ'),
+    ('w', 'ʷ'),
+    ('x', 'ˣ'),
+    ('y', 'ʸ'),
+    ('z', 'ᶻ'),
+];
+
+const SUBSCRIPT_CHARS: &[(char, char)] = &[
+    ('0', '₀'),
+    ('1', '₁'),
+    ('2', '₂'),
+    ('3', '₃'),
+    ('4', '₄'),
+    ('5', '₅'),
+    ('6', '₆'),
+    ('7', '₇'),
+    ('8', '₈'),
+    ('9', '₉'),
+    ('+', '₊'),
+    ('-', '₋'),
+    ('=', '₌'),
+    ('(', '₍'),
+    (')', '₎'),
+    ('a', 'ₐ'),
+    ('e', 'ₑ'),
+    ('h', 'ₕ'),
+    ('i', 'ᵢ'),
+    ('j', 'ⱼ'),
+    ('k', 'ₖ'),
+    ('l', 'ₗ'),
+    ('m', 'ₘ'),
+    ('n', 'ₙ'),
+    ('o', 'ₒ'),
+    ('p', 'ₚ'),
+    ('r', 'ᵣ'),
+    ('s', 'ₛ'),
+    ('t', 'ₜ'),
+    ('u', 'ᵤ'),
+    ('v', 'ᵥ'),
+    ('x', 'ₓ'),
+];
+
+/// A run of text with an extended inline style (`^sup^`, `~sub~`, `==mark==`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Span<'a> {
+    Plain(&'a str),
+    Superscript(&'a str),
+    Subscript(&'a str),
+    Highlight(&'a str),
+}
+
+/// Split text on the Pandoc `^sup^` / `~sub~` and the `==highlight==` extensions.
+///
+/// Delimiters follow the CommonMark flanking rules: the characters just inside a span may not
+/// be whitespace, and `==` must sit on a word boundary, so `a == b` and `a==b==c` stay plain.
+/// Superscript and subscript may not contain spaces and may appear inside words, as in `x^2^`
+/// and `H~2~O`, following Pandoc.
+pub(crate) fn split_spans(text: &str) -> Vec<Span<'_>> {
+    let mut spans = Vec::new();
+    let mut plain_start = 0;
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+        let before = text[..i].chars().next_back();
+        let found = if rest.starts_with("==") && is_boundary(before, '=') {
+            find_highlight(&rest[2..]).map(|end| (Span::Highlight(&rest[2..2 + end]), end + 4))
+        } else if let Some(delimiter) = rest
+            .chars()
+            .next()
+            .filter(|c| (*c == '^' || *c == '~') && before != Some(*c))
+        {
+            rest[1..]
+                .find(delimiter)
+                .filter(|end| {
+                    let inner = &rest[1..1 + *end];
+                    !inner.is_empty()
+                        && !inner.contains(char::is_whitespace)
+                        && !rest[2 + *end..].starts_with(delimiter)
+                })
+                .map(|end| {
+                    let inner = &rest[1..1 + end];
+                    let span = if delimiter == '^' {
+                        Span::Superscript(inner)
+                    } else {
+                        Span::Subscript(inner)
+                    };
+                    (span, end + 2)
+                })
+        } else {
+            None
+        };
+
+        match found {
+            Some((span, len)) => {
+                if plain_start < i {
+                    spans.push(Span::Plain(&text[plain_start..i]));
+                }
+                spans.push(span);
+                i += len;
+                plain_start = i;
+            }
+            None => i += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+
+    if plain_start < text.len() {
+        spans.push(Span::Plain(&text[plain_start..]));
+    }
+    spans
+}
+
+/// Length of the text of a `==highlight==` whose opening `==` precedes `inner`, if it has a
+/// closing `==` that is preceded by non-whitespace and followed by a word boundary
+fn find_highlight(inner: &str) -> Option<usize> {
+    if inner.starts_with(char::is_whitespace) || inner.starts_with('=') {
+        return None;
+    }
+    inner.match_indices("==").map(|(end, _)| end).find(|end| {
+        *end > 0
+            && !inner[..*end].ends_with(char::is_whitespace)
+            && is_boundary(inner[end + 2..].chars().next(), '=')
+    })
+}
+
+/// Whether the character outside a delimiter leaves it on a word boundary
+fn is_boundary(outside: Option<char>, delimiter: char) -> bool {
+    outside.is_none_or(|c| !c.is_alphanumeric() && c != delimiter)
+}
+
+/// Render text with its extended inline styles applied
+pub(crate) fn style_text(text: &str) -> String {
+    split_spans(text)
+        .into_iter()
+        .map(|span| match span {
+            Span::Plain(text) => text.to_string(),
+            Span::Superscript(text) => {
+                unicode_superscript(text).unwrap_or_else(|| format!("^{}^", text))
+            }
+            Span::Subscript(text) => {
+                unicode_subscript(text).unwrap_or_else(|| format!("~{}~", text))
+            }
+            Span::Highlight(text) => highlight(text),
+        })
+        .collect()
+}
+
+/// Render text as superscript with Unicode superscript characters, or unchanged when a
+/// character has none
+pub(crate) fn superscript(text: &str) -> String {
+    unicode_superscript(text).unwrap_or_else(|| text.to_string())
+}
+
+/// Render text as subscript with Unicode subscript characters, or unchanged when a character
+/// has none
+pub(crate) fn subscript(text: &str) -> String {
+    unicode_subscript(text).unwrap_or_else(|| text.to_string())
+}
+
+/// Render `==marked==` text with a background color
+pub(crate) fn highlight(text: &str) -> String {
+    text.black().on_yellow().to_string()
+}
+
+/// Render GFM `~~deleted~~` text with SGR 9
+pub(crate) fn strikethrough(text: &str) -> String {
+    text.strikethrough().to_string()
+}
+
+/// Whether a GFM delete node was written with single tildes, i.e. is a Pandoc `~subscript~`
+pub(crate) fn is_subscript(delete: &Delete) -> bool {
+    let [Node::Text(text)] = delete.values.as_slice() else {
+        return false;
+    };
+    let Some(position) = &delete.position else {
+        return false;
+    };
+    position.start.line == position.end.line
+        && !text.value.contains(char::is_whitespace)
+        && position.end.column - position.start.column == text.value.chars().count() + 2
+}
+
+/// Text in Unicode superscript characters, if every character has one
+pub(crate) fn unicode_superscript(text: &str) -> Option<String> {
+    map_chars(text, SUPERSCRIPT_CHARS)
+}
+
+/// Text in Unicode subscript characters, if every character has one
+pub(crate) fn unicode_subscript(text: &str) -> Option<String> {
+    map_chars(text, SUBSCRIPT_CHARS)
+}
+
+fn map_chars(text: &str, table: &[(char, char)]) -> Option<String> {
+    text.chars()
+        .map(|c| table.iter().find(|(from, _)| *from == c).map(|(_, to)| *to))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mq_markdown::Markdown;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("plain", vec![Span::Plain("plain")])]
+    #[case("x^2^", vec![Span::Plain("x"), Span::Superscript("2")])]
+    #[case("H~2~O", vec![Span::Plain("H"), Span::Subscript("2"), Span::Plain("O")])]
+    #[case("a ==marked== b", vec![Span::Plain("a "), Span::Highlight("marked"), Span::Plain(" b")])]
+    #[case("a ^not sup^", vec![Span::Plain("a ^not sup^")])]
+    #[case("a == b", vec![Span::Plain("a == b")])]
+    #[case("é^2^", vec![Span::Plain("é"), Span::Superscript("2")])]
+    #[case("If a == b or c == d then x^2", vec![Span::Plain("If a == b or c == d then x^2")])]
+    #[case("a==b==c", vec![Span::Plain("a==b==c")])]
+    #[case("(==marked==)", vec![Span::Plain("("), Span::Highlight("marked"), Span::Plain(")")])]
+    #[case("=== a ===", vec![Span::Plain("=== a ===")])]
+    #[case("a ^^b^^", vec![Span::Plain("a ^^b^^")])]
+    fn test_split_spans(#[case] text: &str, #[case] expected: Vec<Span>) {
+        assert_eq!(split_spans(text), expected);
+    }
+
+    #[rstest]
+    #[case("2", "²")]
+    #[case("(n+1)", "⁽ⁿ⁺¹⁾")]
+    #[case("th", "ᵗʰ")]
+    #[case("Q", "Q")]
+    fn test_superscript(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(superscript(text), expected);
+    }
+
+    #[rstest]
+    #[case("2", "₂")]
+    #[case("x-1", "ₓ₋₁")]
+    #[case("max", "ₘₐₓ")]
+    #[case("by", "by")]
+    fn test_subscript(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(subscript(text), expected);
+    }
+
+    #[rstest]
+    #[case("H~2~O", "H₂O")]
+    #[case("x^Q^", "x^Q^")]
+    #[case("a == b", "a == b")]
+    fn test_style_text(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(style_text(text), expected);
+    }
+
+    #[rstest]
+    #[case("H~2~O", true)]
+    #[case("~~gone~~", false)]
+    fn test_is_subscript(#[case] source: &str, #[case] expected: bool) {
+        let markdown: Markdown = source.parse().unwrap();
+        let Some(Node::Fragment(fragment)) = markdown.nodes.first() else {
+            panic!("expected a paragraph");
+        };
+        let delete = fragment
+            .values
+            .iter()
+            .find_map(|node| match node {
+                Node::Delete(delete) => Some(delete),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(is_subscript(delete), expected);
+    }
+}
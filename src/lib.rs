@@ -14,8 +14,35 @@
 //! println!("{}", rendered);
 //! ```
 
+mod callout;
+mod check;
+mod definitions;
+mod diff;
+mod figlet;
+mod footnotes;
+mod front_matter;
+mod heading;
 mod highlighter;
+mod html;
+mod inline_style;
+mod math;
+mod mdx;
+mod numbering;
 mod renderer;
+mod table;
+mod tasks;
+mod width;
 
-pub use highlighter::SyntaxHighlighter;
-pub use renderer::{render_markdown, render_markdown_to_string};
+pub use callout::{CalloutBox, CalloutStyle, Callouts};
+pub use check::{CodeBlockDiagnostic, check_code_blocks};
+pub use figlet::BannerFont;
+pub use front_matter::FrontMatterMode;
+pub use heading::HeadingStyle;
+pub use highlighter::{SyntaxError, SyntaxHighlighter};
+pub use numbering::ListNumbering;
+pub use renderer::{
+    DEFAULT_WIDTH, RenderOptions, render_markdown, render_markdown_to_string,
+    render_markdown_to_string_with_options, render_markdown_with_options,
+    render_markdown_with_source,
+};
+pub use tasks::{TaskItem, task_items, toggle_task};
@@ -1,11 +1,14 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use miette::{IntoDiagnostic, Result};
 use mq_markdown::Markdown;
-use mqv::render_markdown;
+use mqv::{
+    BannerFont, CalloutBox, DEFAULT_WIDTH, FrontMatterMode, HeadingStyle, ListNumbering,
+    RenderOptions, check_code_blocks, render_markdown_with_source, task_items, toggle_task,
+};
 use std::fs;
 use std::io::{self, BufWriter, Write};
 use std::io::{IsTerminal, Read};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Parser, Debug)]
 #[command(name = "mqv")]
@@ -16,13 +19,206 @@ pub struct Args {
     /// Markdown file to view
     #[arg(value_name = "FILE")]
     file: Option<PathBuf>,
+
+    /// Underline syntax errors inside fenced code blocks
+    #[arg(long)]
+    syntax_errors: bool,
+
+    /// Render ```markdown fences as a live preview instead of their source
+    #[arg(long)]
+    preview_markdown: bool,
+
+    /// How h1 and h2 headings are drawn
+    #[arg(long, value_enum, default_value_t = HeadingStyleArg::Rules)]
+    heading_style: HeadingStyleArg,
+
+    /// Font used by the banner heading style
+    #[arg(long, value_enum, default_value_t = BannerFontArg::HalfBlock)]
+    banner_font: BannerFontArg,
+
+    /// Also render h2 headings as banners
+    #[arg(long)]
+    banner_h2: bool,
+
+    /// Layout width in columns (defaults to the terminal width)
+    #[arg(long)]
+    width: Option<usize>,
+
+    /// How YAML/TOML front matter is displayed
+    #[arg(long, value_enum, default_value_t = FrontMatterArg::Panel)]
+    front_matter: FrontMatterArg,
+
+    /// Show the body of collapsed <details> sections and folded callouts
+    #[arg(long)]
+    expand_details: bool,
+
+    /// How ordered list items are numbered
+    #[arg(long, value_enum, default_value_t = ListNumberingArg::Decimal)]
+    list_numbering: ListNumberingArg,
+
+    /// How the box around callouts is drawn
+    #[arg(long, value_enum, default_value_t = CalloutBoxArg::Open)]
+    callout_box: CalloutBoxArg,
+
+    /// Render ^superscript^, ~subscript~ and ==highlight== extensions
+    #[arg(long)]
+    inline_extensions: bool,
+
+    /// Right-align table columns of numbers that have no explicit alignment
+    #[arg(long)]
+    align_numbers: bool,
+
+    /// Parse the input as MDX (the default for .mdx files)
+    #[arg(long)]
+    mdx: bool,
+
+    /// Report code blocks whose declared language does not parse, instead of rendering
+    #[arg(long)]
+    check: bool,
+
+    /// Show a progress bar under each task list and a summary for the document
+    #[arg(long)]
+    task_progress: bool,
+
+    /// Print only the task items with the headings they are under, instead of rendering
+    #[arg(long)]
+    tasks: bool,
+
+    /// Toggle the checkbox of the task item on this line of FILE, editing FILE in place
+    #[arg(long, value_name = "LINE", requires = "file")]
+    toggle_task: Option<usize>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum HeadingStyleArg {
+    /// Decorative rules around the heading text
+    Rules,
+    /// Large text if the terminal supports it, otherwise rules
+    Large,
+    /// Kitty text sizing protocol (OSC 66)
+    Kitty,
+    /// DEC double-height and double-width lines
+    DoubleHeight,
+    /// Big block-letter banners using a bundled FIGlet font
+    Banner,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum BannerFontArg {
+    /// Unicode half blocks, three rows tall
+    HalfBlock,
+    /// Full blocks, five rows tall
+    Block,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum FrontMatterArg {
+    /// Do not show front matter
+    Hide,
+    /// Show the front matter source with highlighting
+    Raw,
+    /// Show title, author, date and tags in a metadata box
+    Panel,
+}
+
+impl From<FrontMatterArg> for FrontMatterMode {
+    fn from(arg: FrontMatterArg) -> Self {
+        match arg {
+            FrontMatterArg::Hide => FrontMatterMode::Hide,
+            FrontMatterArg::Raw => FrontMatterMode::Raw,
+            FrontMatterArg::Panel => FrontMatterMode::Panel,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum CalloutBoxArg {
+    /// Left border only
+    Open,
+    /// Closed box fitted to the content
+    Fit,
+    /// Closed box spanning the layout width
+    Full,
+}
+
+impl From<CalloutBoxArg> for CalloutBox {
+    fn from(arg: CalloutBoxArg) -> Self {
+        match arg {
+            CalloutBoxArg::Open => CalloutBox::Open,
+            CalloutBoxArg::Fit => CalloutBox::Fit,
+            CalloutBoxArg::Full => CalloutBox::Full,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum ListNumberingArg {
+    /// 1. at every level
+    Decimal,
+    /// 1. / a. / i. by nesting depth
+    Depth,
+    /// 1. / 1.1. / 1.1.1.
+    Hierarchical,
+}
+
+impl From<ListNumberingArg> for ListNumbering {
+    fn from(arg: ListNumberingArg) -> Self {
+        match arg {
+            ListNumberingArg::Decimal => ListNumbering::Decimal,
+            ListNumberingArg::Depth => ListNumbering::ByDepth,
+            ListNumberingArg::Hierarchical => ListNumbering::Hierarchical,
+        }
+    }
+}
+
+impl From<BannerFontArg> for BannerFont {
+    fn from(arg: BannerFontArg) -> Self {
+        match arg {
+            BannerFontArg::HalfBlock => BannerFont::HalfBlock,
+            BannerFontArg::Block => BannerFont::Block,
+        }
+    }
+}
+
+impl Args {
+    fn heading_style(&self) -> HeadingStyle {
+        match self.heading_style {
+            HeadingStyleArg::Rules => HeadingStyle::Rules,
+            HeadingStyleArg::Large => HeadingStyle::detect(),
+            HeadingStyleArg::Kitty => HeadingStyle::Kitty,
+            HeadingStyleArg::DoubleHeight => HeadingStyle::DoubleHeight,
+            HeadingStyleArg::Banner => HeadingStyle::Banner {
+                font: self.banner_font.into(),
+                include_h2: self.banner_h2,
+            },
+        }
+    }
+
+    fn is_mdx(&self) -> bool {
+        self.mdx
+            || self.file.as_ref().is_some_and(|file| {
+                file.extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("mdx"))
+            })
+    }
+
+    fn width(&self) -> usize {
+        self.width.unwrap_or_else(|| {
+            if io::stdout().is_terminal() {
+                viuer::terminal_size().0 as usize
+            } else {
+                DEFAULT_WIDTH
+            }
+        })
+    }
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
-    let content = if io::stdin().is_terminal() {
-        if let Some(file) = args.file {
-            fs::read_to_string(&file).into_diagnostic()?
+    // Toggling a task always edits FILE, even when stdin is piped
+    let content = if io::stdin().is_terminal() || args.toggle_task.is_some() {
+        if let Some(file) = &args.file {
+            fs::read_to_string(file).into_diagnostic()?
         } else {
             return Err(miette::miette!("No input file specified"));
         }
@@ -31,12 +227,111 @@ fn main() -> Result<()> {
         io::stdin().read_to_string(&mut buffer).into_diagnostic()?;
         buffer
     };
-    let markdown: Markdown = content.parse().map_err(|e| miette::miette!("{}", e))?;
+    let markdown = if args.is_mdx() {
+        Markdown::from_mdx_str(&content).map_err(|e| miette::miette!("{}", e))?
+    } else {
+        content.parse().map_err(|e| miette::miette!("{}", e))?
+    };
+
+    if let (Some(line), Some(file)) = (args.toggle_task, &args.file) {
+        return toggle(&markdown, &content, file, line);
+    }
+
+    if args.check {
+        return check(&markdown, &args);
+    }
+
+    if args.tasks {
+        print_tasks(&markdown);
+        return Ok(());
+    }
+
+    let options = RenderOptions {
+        mark_syntax_errors: args.syntax_errors,
+        preview_markdown: args.preview_markdown,
+        heading_style: args.heading_style(),
+        width: args.width(),
+        front_matter: args.front_matter.into(),
+        expand_details: args.expand_details,
+        callout_box: args.callout_box.into(),
+        list_numbering: args.list_numbering.into(),
+        task_progress: args.task_progress,
+        align_numbers: args.align_numbers,
+        inline_extensions: args.inline_extensions,
+        ..Default::default()
+    };
 
     let stdout = io::stdout();
     let mut writer = BufWriter::new(stdout.lock());
-    render_markdown(&markdown, &mut writer).into_diagnostic()?;
+    render_markdown_with_source(&markdown, &content, &mut writer, &options).into_diagnostic()?;
     writer.flush().into_diagnostic()?;
 
     Ok(())
 }
+
+/// Print one line per syntax error and fail if any code block does not parse
+fn check(markdown: &Markdown, args: &Args) -> Result<()> {
+    let name = args
+        .file
+        .as_ref()
+        .map(|file| file.display().to_string())
+        .unwrap_or_else(|| "<stdin>".to_string());
+    let diagnostics = check_code_blocks(markdown);
+
+    for diagnostic in &diagnostics {
+        for error in &diagnostic.errors {
+            match diagnostic.source_line(error) {
+                Some(line) => println!(
+                    "{}:{}:{}: {}: {}",
+                    name, line, error.column, diagnostic.lang, error.message
+                ),
+                None => println!("{}: {}: {}", name, diagnostic.lang, error.message),
+            }
+        }
+    }
+
+    if diagnostics.is_empty() {
+        Ok(())
+    } else {
+        Err(miette::miette!(
+            "{} code block(s) failed to parse",
+            diagnostics.len()
+        ))
+    }
+}
+
+/// Print the task items grouped by the headings they are under
+fn print_tasks(markdown: &Markdown) {
+    let tasks = task_items(markdown);
+    let mut heading_path = None;
+
+    for task in &tasks {
+        if heading_path != Some(&task.heading_path) {
+            if heading_path.is_some() {
+                println!();
+            }
+            if !task.heading_path.is_empty() {
+                println!("{}", task.heading_path.join(" > "));
+            }
+            heading_path = Some(&task.heading_path);
+        }
+        let checkbox = if task.checked { "[x]" } else { "[ ]" };
+        match task.line {
+            Some(line) => println!("{:>5}: {} {}", line, checkbox, task.text),
+            None => println!("       {} {}", checkbox, task.text),
+        }
+    }
+
+    if !tasks.is_empty() {
+        let done = tasks.iter().filter(|task| task.checked).count();
+        println!();
+        println!("{}/{} done", done, tasks.len());
+    }
+}
+
+/// Toggle the checkbox of the task item on a line of the file and write it back
+fn toggle(markdown: &Markdown, source: &str, file: &Path, line: usize) -> Result<()> {
+    let toggled = toggle_task(markdown, source, line)
+        .ok_or_else(|| miette::miette!("{}:{}: not a task list item", file.display(), line))?;
+    fs::write(file, toggled).into_diagnostic()
+}
@@ -0,0 +1,898 @@
+use crate::inline_style::{unicode_subscript, unicode_superscript};
+use crate::width::display_width;
+
+/// LaTeX commands that translate to a single Unicode symbol
+const SYMBOLS: &[(&str, &str)] = &[
+    ("alpha", "α"),
+    ("beta", "β"),
+    ("gamma", "γ"),
+    ("delta", "δ"),
+    ("epsilon", "ε"),
+    ("varepsilon", "ε"),
+    ("zeta", "ζ"),
+    ("eta", "η"),
+    ("theta", "θ"),
+    ("vartheta", "ϑ"),
+    ("iota", "ι"),
+    ("kappa", "κ"),
+    ("lambda", "λ"),
+    ("mu", "μ"),
+    ("nu", "ν"),
+    ("xi", "ξ"),
+    ("pi", "π"),
+    ("rho", "ρ"),
+    ("sigma", "σ"),
+    ("tau", "τ"),
+    ("upsilon", "υ"),
+    ("phi", "φ"),
+    ("varphi", "φ"),
+    ("chi", "χ"),
+    ("psi", "ψ"),
+    ("omega", "ω"),
+    ("Gamma", "Γ"),
+    ("Delta", "Δ"),
+    ("Theta", "Θ"),
+    ("Lambda", "Λ"),
+    ("Xi", "Ξ"),
+    ("Pi", "Π"),
+    ("Sigma", "Σ"),
+    ("Upsilon", "Υ"),
+    ("Phi", "Φ"),
+    ("Psi", "Ψ"),
+    ("Omega", "Ω"),
+    ("times", "×"),
+    ("cdot", "⋅"),
+    ("pm", "±"),
+    ("mp", "∓"),
+    ("div", "÷"),
+    ("ast", "∗"),
+    ("circ", "∘"),
+    ("leq", "≤"),
+    ("le", "≤"),
+    ("geq", "≥"),
+    ("ge", "≥"),
+    ("neq", "≠"),
+    ("ne", "≠"),
+    ("approx", "≈"),
+    ("equiv", "≡"),
+    ("sim", "∼"),
+    ("propto", "∝"),
+    ("ll", "≪"),
+    ("gg", "≫"),
+    ("infty", "∞"),
+    ("partial", "∂"),
+    ("nabla", "∇"),
+    ("in", "∈"),
+    ("notin", "∉"),
+    ("subset", "⊂"),
+    ("subseteq", "⊆"),
+    ("supset", "⊃"),
+    ("supseteq", "⊇"),
+    ("cup", "∪"),
+    ("cap", "∩"),
+    ("emptyset", "∅"),
+    ("forall", "∀"),
+    ("exists", "∃"),
+    ("neg", "¬"),
+    ("land", "∧"),
+    ("wedge", "∧"),
+    ("lor", "∨"),
+    ("vee", "∨"),
+    ("to", "→"),
+    ("rightarrow", "→"),
+    ("leftarrow", "←"),
+    ("mapsto", "↦"),
+    ("Rightarrow", "⇒"),
+    ("Leftarrow", "⇐"),
+    ("implies", "⟹"),
+    ("iff", "⟺"),
+    ("cdots", "⋯"),
+    ("ldots", "…"),
+    ("dots", "…"),
+    ("vdots", "⋮"),
+    ("ddots", "⋱"),
+    ("mid", "∣"),
+    ("ell", "ℓ"),
+    ("hbar", "ℏ"),
+    ("langle", "⟨"),
+    ("rangle", "⟩"),
+    ("lfloor", "⌊"),
+    ("rfloor", "⌋"),
+    ("lceil", "⌈"),
+    ("rceil", "⌉"),
+    ("prime", "′"),
+    ("quad", "  "),
+    ("qquad", "    "),
+];
+
+/// Large operators, and whether their limits are stacked above and below in display math
+const LARGE_OPERATORS: &[(&str, &str, bool)] = &[
+    ("sum", "∑", true),
+    ("prod", "∏", true),
+    ("coprod", "∐", true),
+    ("bigcup", "⋃", true),
+    ("bigcap", "⋂", true),
+    ("lim", "lim", true),
+    ("max", "max", true),
+    ("min", "min", true),
+    ("sup", "sup", true),
+    ("inf", "inf", true),
+    ("argmax", "argmax", true),
+    ("argmin", "argmin", true),
+    ("int", "∫", false),
+    ("iint", "∬", false),
+    ("iiint", "∭", false),
+    ("oint", "∮", false),
+];
+
+/// Function names typeset upright
+const FUNCTIONS: &[&str] = &[
+    "sin", "cos", "tan", "cot", "sec", "csc", "sinh", "cosh", "tanh", "arcsin", "arccos", "arctan",
+    "log", "ln", "lg", "exp", "det", "dim", "ker", "deg", "gcd", "Pr",
+];
+
+const DOUBLE_STRUCK: &[(char, char)] = &[
+    ('C', 'ℂ'),
+    ('N', 'ℕ'),
+    ('P', 'ℙ'),
+    ('Q', 'ℚ'),
+    ('R', 'ℝ'),
+    ('Z', 'ℤ'),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Text(String),
+    Group(Vec<Expr>),
+    Frac(Box<Expr>, Box<Expr>),
+    Sqrt(Option<String>, Box<Expr>),
+    Operator(&'static str, bool),
+    Scripts {
+        base: Box<Expr>,
+        sub: Option<Box<Expr>>,
+        sup: Option<Box<Expr>>,
+    },
+    Matrix {
+        env: String,
+        rows: Vec<Vec<Expr>>,
+    },
+}
+
+/// Translate inline LaTeX math to a single line of Unicode text.
+///
+/// Sub- and superscripts use Unicode script characters where every character has one,
+/// and fall back to `_(…)` / `^(…)` otherwise.
+pub(crate) fn render_inline(source: &str) -> String {
+    inline(&parse(source)).trim().to_string()
+}
+
+/// Lay out display LaTeX math as a multi-line block, with fractions, roots, limits and
+/// matrices drawn over several rows.
+pub(crate) fn render_display(source: &str) -> Vec<String> {
+    layout(&parse(source))
+        .lines
+        .into_iter()
+        .map(|line| line.trim_end().to_string())
+        .collect()
+}
+
+fn parse(source: &str) -> Expr {
+    let mut parser = Parser {
+        chars: source.trim().chars().collect(),
+        pos: 0,
+    };
+    let mut rows = parser.parse_rows();
+    // Stray closing braces or `\end` would otherwise stop parsing early
+    while parser.pos < parser.chars.len() {
+        parser.pos += 1;
+        rows.extend(parser.parse_rows());
+    }
+
+    if rows.len() == 1 && rows[0].len() == 1 {
+        rows.remove(0).remove(0)
+    } else {
+        Expr::Matrix {
+            env: "aligned".to_string(),
+            rows,
+        }
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn at_end_command(&self) -> bool {
+        self.chars[self.pos..].starts_with(&['\\', 'e', 'n', 'd'])
+    }
+
+    /// Parse `&`-separated cells and `\\`-separated rows
+    fn parse_rows(&mut self) -> Vec<Vec<Expr>> {
+        let mut rows = Vec::new();
+        let mut cells = Vec::new();
+        loop {
+            cells.push(Expr::Group(self.parse_sequence()));
+            match (self.peek(), self.peek_at(1)) {
+                (Some('&'), _) => self.pos += 1,
+                (Some('\\'), Some('\\')) => {
+                    self.pos += 2;
+                    rows.push(std::mem::take(&mut cells));
+                }
+                _ => break,
+            }
+        }
+        rows.push(cells);
+        // A trailing `\\` leaves an empty last row
+        if rows.len() > 1
+            && rows
+                .last()
+                .is_some_and(|row| row.iter().all(|cell| *cell == Expr::Group(Vec::new())))
+        {
+            rows.pop();
+        }
+        rows
+    }
+
+    fn parse_sequence(&mut self) -> Vec<Expr> {
+        let mut exprs: Vec<Expr> = Vec::new();
+        while let Some(c) = self.peek() {
+            match c {
+                '}' | '&' => break,
+                '\\' if self.peek_at(1) == Some('\\') || self.at_end_command() => break,
+                '^' | '_' => {
+                    self.pos += 1;
+                    let script = Box::new(self.parse_argument());
+                    while exprs.last() == Some(&Expr::Text(" ".to_string())) {
+                        exprs.pop();
+                    }
+                    let base = exprs.pop().unwrap_or(Expr::Text(String::new()));
+                    let (base, mut sub, mut sup) = match base {
+                        Expr::Scripts { base, sub, sup } => (base, sub, sup),
+                        base => (Box::new(base), None, None),
+                    };
+                    if c == '^' {
+                        sup = Some(script);
+                    } else {
+                        sub = Some(script);
+                    }
+                    exprs.push(Expr::Scripts { base, sub, sup });
+                }
+                c if c.is_whitespace() => {
+                    self.skip_whitespace();
+                    let after_space =
+                        matches!(exprs.last(), Some(Expr::Text(text)) if text.ends_with(' '));
+                    if !exprs.is_empty() && !after_space {
+                        exprs.push(Expr::Text(" ".to_string()));
+                    }
+                }
+                '{' => {
+                    self.pos += 1;
+                    let group = self.parse_sequence();
+                    self.expect('}');
+                    exprs.push(Expr::Group(group));
+                }
+                '\\' => {
+                    self.pos += 1;
+                    exprs.push(self.parse_command());
+                }
+                '\'' => {
+                    self.pos += 1;
+                    exprs.push(Expr::Text("′".to_string()));
+                }
+                c => {
+                    self.pos += 1;
+                    exprs.push(Expr::Text(c.to_string()));
+                }
+            }
+        }
+        while exprs.last() == Some(&Expr::Text(" ".to_string())) {
+            exprs.pop();
+        }
+        exprs
+    }
+
+    fn expect(&mut self, c: char) {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+        }
+    }
+
+    /// Parse a command or group argument: `{...}`, `\command` or a single character
+    fn parse_argument(&mut self) -> Expr {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => {
+                self.pos += 1;
+                let group = self.parse_sequence();
+                self.expect('}');
+                Expr::Group(group)
+            }
+            Some('\\') => {
+                self.pos += 1;
+                self.parse_command()
+            }
+            Some(c) => {
+                self.pos += 1;
+                Expr::Text(c.to_string())
+            }
+            None => Expr::Text(String::new()),
+        }
+    }
+
+    /// Raw text of a `{...}` argument, for `\text` and environment names
+    fn parse_raw_argument(&mut self) -> String {
+        self.skip_whitespace();
+        if self.peek() != Some('{') {
+            return self.parse_argument_text();
+        }
+        self.pos += 1;
+        let mut depth = 0;
+        let mut text = String::new();
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                '{' => depth += 1,
+                '}' if depth == 0 => break,
+                '}' => depth -= 1,
+                _ => {}
+            }
+            text.push(c);
+        }
+        text
+    }
+
+    fn parse_argument_text(&mut self) -> String {
+        inline(&self.parse_argument())
+    }
+
+    fn parse_command(&mut self) -> Expr {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+        let name: String = self.chars[start..self.pos].iter().collect();
+
+        if name.is_empty() {
+            let Some(c) = self.peek() else {
+                return Expr::Text("\\".to_string());
+            };
+            self.pos += 1;
+            let text = match c {
+                ',' | ';' | ':' | ' ' => " ",
+                '!' => "",
+                '|' => "‖",
+                _ => return Expr::Text(c.to_string()),
+            };
+            return Expr::Text(text.to_string());
+        }
+
+        match name.as_str() {
+            "frac" | "dfrac" | "tfrac" => {
+                let numerator = self.parse_argument();
+                let denominator = self.parse_argument();
+                Expr::Frac(Box::new(numerator), Box::new(denominator))
+            }
+            "sqrt" => {
+                self.skip_whitespace();
+                let index = if self.peek() == Some('[') {
+                    self.pos += 1;
+                    let start = self.pos;
+                    while self.peek().is_some_and(|c| c != ']') {
+                        self.pos += 1;
+                    }
+                    let index: String = self.chars[start..self.pos].iter().collect();
+                    self.expect(']');
+                    Some(index.trim().to_string())
+                } else {
+                    None
+                };
+                Expr::Sqrt(index, Box::new(self.parse_argument()))
+            }
+            "text" | "textrm" | "textit" | "textbf" | "mbox" | "operatorname" => {
+                Expr::Text(self.parse_raw_argument())
+            }
+            "mathrm" | "mathit" | "mathbf" | "mathsf" | "mathcal" | "boldsymbol" => {
+                self.parse_argument()
+            }
+            "mathbb" => Expr::Text(
+                self.parse_raw_argument()
+                    .chars()
+                    .map(|c| {
+                        DOUBLE_STRUCK
+                            .iter()
+                            .find(|(from, _)| *from == c)
+                            .map_or(c, |(_, to)| *to)
+                    })
+                    .collect(),
+            ),
+            "left" | "right" | "big" | "Big" | "bigg" | "Bigg" | "bigl" | "bigr" => {
+                self.skip_whitespace();
+                match self.parse_argument() {
+                    Expr::Text(text) if text == "." => Expr::Text(String::new()),
+                    delimiter => delimiter,
+                }
+            }
+            "begin" => {
+                let env = self.parse_raw_argument();
+                let rows = self.parse_rows();
+                if self.at_end_command() {
+                    self.pos += 4;
+                    self.parse_raw_argument();
+                }
+                Expr::Matrix { env, rows }
+            }
+            name => {
+                if let Some((_, symbol, limits)) = LARGE_OPERATORS.iter().find(|(n, ..)| *n == name)
+                {
+                    Expr::Operator(symbol, *limits)
+                } else if let Some((_, symbol)) = SYMBOLS.iter().find(|(n, _)| *n == name) {
+                    Expr::Text(symbol.to_string())
+                } else if FUNCTIONS.contains(&name) {
+                    Expr::Text(name.to_string())
+                } else {
+                    Expr::Text(format!("\\{}", name))
+                }
+            }
+        }
+    }
+}
+
+fn matrix_delimiters(env: &str) -> (&'static str, &'static str) {
+    match env {
+        "pmatrix" => ("(", ")"),
+        "bmatrix" => ("[", "]"),
+        "Bmatrix" => ("{", "}"),
+        "vmatrix" => ("|", "|"),
+        "Vmatrix" => ("‖", "‖"),
+        "cases" => ("{", ""),
+        _ => ("", ""),
+    }
+}
+
+/// Whether matrix cells are left-aligned and joined by a single space, as in `aligned`
+fn is_alignment_env(env: &str) -> bool {
+    matches!(
+        env,
+        "aligned" | "align" | "align*" | "split" | "gathered" | "eqnarray"
+    )
+}
+
+fn inline(expr: &Expr) -> String {
+    match expr {
+        Expr::Text(text) => text.clone(),
+        Expr::Group(exprs) => exprs.iter().map(inline).collect(),
+        Expr::Frac(numerator, denominator) => format!(
+            "{}/{}",
+            parenthesize(&inline(numerator)),
+            parenthesize(&inline(denominator))
+        ),
+        Expr::Sqrt(index, body) => {
+            let radical = match index.as_deref() {
+                None => "√".to_string(),
+                Some("3") => "∛".to_string(),
+                Some("4") => "∜".to_string(),
+                Some(index) => format!("{}√", script(index, '^')),
+            };
+            format!("{}{}", radical, parenthesize(&inline(body)))
+        }
+        Expr::Operator(symbol, _) => symbol.to_string(),
+        Expr::Scripts { base, sub, sup } => {
+            let mut text = inline(base);
+            if let Some(sub) = sub {
+                text.push_str(&script(&inline(sub), '_'));
+            }
+            if let Some(sup) = sup {
+                text.push_str(&script(&inline(sup), '^'));
+            }
+            text
+        }
+        Expr::Matrix { env, rows } => {
+            let (open, close) = matrix_delimiters(env);
+            let rows: Vec<String> = rows
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|cell| inline(cell).trim().to_string())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect();
+            format!("{}{}{}", open, rows.join("; "), close)
+        }
+    }
+}
+
+/// Wrap compound text in parentheses so it reads unambiguously on one line
+fn parenthesize(text: &str) -> String {
+    if text.chars().count() > 1 && !text.chars().all(char::is_alphanumeric) {
+        format!("({})", text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// A sub- or superscript in Unicode script characters, or `_x` / `^(…)` if there are none
+fn script(text: &str, marker: char) -> String {
+    let unicode = if marker == '^' {
+        unicode_superscript(text)
+    } else {
+        unicode_subscript(text)
+    };
+    unicode.unwrap_or_else(|| {
+        if text.chars().count() == 1 {
+            format!("{}{}", marker, text)
+        } else {
+            format!("{}({})", marker, text)
+        }
+    })
+}
+
+/// A rectangular block of text lines with the row that aligns with the surrounding baseline
+#[derive(Debug, Clone)]
+struct Block {
+    lines: Vec<String>,
+    baseline: usize,
+}
+
+impl Block {
+    fn text(text: impl Into<String>) -> Self {
+        Self {
+            lines: vec![text.into()],
+            baseline: 0,
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.lines
+            .iter()
+            .map(|line| display_width(line))
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn height(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Lines padded to `width` with the block centered horizontally
+    fn centered_lines(&self, width: usize) -> Vec<String> {
+        let left = width.saturating_sub(self.width()) / 2;
+        self.lines
+            .iter()
+            .map(|line| {
+                let right = width.saturating_sub(left + display_width(line));
+                format!("{}{}{}", " ".repeat(left), line, " ".repeat(right))
+            })
+            .collect()
+    }
+
+    /// Lines padded to `width` with the block left-aligned
+    fn padded_lines(&self, width: usize) -> Vec<String> {
+        self.lines
+            .iter()
+            .map(|line| {
+                let right = width.saturating_sub(display_width(line));
+                format!("{}{}", line, " ".repeat(right))
+            })
+            .collect()
+    }
+
+    /// Place blocks side by side, aligned on their baselines
+    fn beside(blocks: &[Block]) -> Self {
+        let above = blocks.iter().map(|b| b.baseline).max().unwrap_or(0);
+        let below = blocks
+            .iter()
+            .map(|b| b.height() - b.baseline - 1)
+            .max()
+            .unwrap_or(0);
+        let height = above + below + 1;
+        let mut lines = vec![String::new(); height];
+
+        for block in blocks {
+            let width = block.width();
+            let offset = above - block.baseline;
+            let padded = block.padded_lines(width);
+            for (row, line) in lines.iter_mut().enumerate() {
+                match row.checked_sub(offset).and_then(|r| padded.get(r)) {
+                    Some(text) => line.push_str(text),
+                    None => line.push_str(&" ".repeat(width)),
+                }
+            }
+        }
+
+        Self {
+            lines,
+            baseline: above,
+        }
+    }
+}
+
+fn layout(expr: &Expr) -> Block {
+    match expr {
+        Expr::Text(text) => Block::text(text.clone()),
+        Expr::Group(exprs) => {
+            if exprs.is_empty() {
+                Block::text("")
+            } else {
+                Block::beside(&exprs.iter().map(layout).collect::<Vec<_>>())
+            }
+        }
+        Expr::Frac(numerator, denominator) => {
+            let numerator = layout(numerator);
+            let denominator = layout(denominator);
+            let width = numerator.width().max(denominator.width()) + 2;
+            let mut lines = numerator.centered_lines(width);
+            lines.push("─".repeat(width));
+            lines.extend(denominator.centered_lines(width));
+            Block {
+                lines,
+                baseline: numerator.height(),
+            }
+        }
+        Expr::Sqrt(index, body) => {
+            let body = layout(body);
+            let radical = match index.as_deref() {
+                None => "√",
+                Some("3") => "∛",
+                Some("4") => "∜",
+                Some(_) => "√",
+            };
+            let mut lines = vec![format!(" {}", "_".repeat(body.width()))];
+            let last = body.height() - 1;
+            for (row, line) in body.padded_lines(body.width()).into_iter().enumerate() {
+                let prefix = if row == last { radical } else { "│" };
+                lines.push(format!("{}{}", prefix, line));
+            }
+            Block {
+                lines,
+                baseline: body.baseline + 1,
+            }
+        }
+        Expr::Operator(symbol, _) => Block::text(*symbol),
+        Expr::Scripts { base, sub, sup } => layout_scripts(base, sub.as_deref(), sup.as_deref()),
+        Expr::Matrix { env, rows } => layout_matrix(env, rows),
+    }
+}
+
+fn layout_scripts(base: &Expr, sub: Option<&Expr>, sup: Option<&Expr>) -> Block {
+    let base_block = layout(base);
+    let sub_block = sub.map(layout);
+    let sup_block = sup.map(layout);
+
+    // Limits of sums, products and the like are stacked above and below the operator
+    if let Expr::Operator(_, true) = base {
+        let width = [&sup_block, &Some(base_block.clone()), &sub_block]
+            .iter()
+            .filter_map(|b| b.as_ref().map(Block::width))
+            .max()
+            .unwrap_or(0);
+        let mut lines = Vec::new();
+        if let Some(sup) = &sup_block {
+            lines.extend(sup.centered_lines(width));
+        }
+        let baseline = lines.len() + base_block.baseline;
+        lines.extend(base_block.centered_lines(width));
+        if let Some(sub) = &sub_block {
+            lines.extend(sub.centered_lines(width));
+        }
+        return Block { lines, baseline };
+    }
+
+    // Single-line scripts with Unicode script characters stay on the baseline
+    let single_line = |block: &Option<Block>| block.as_ref().is_none_or(|b| b.height() == 1);
+    if single_line(&sub_block) && single_line(&sup_block) {
+        let sub_text = sub.map(|s| unicode_subscript(&inline(s)));
+        let sup_text = sup.map(|s| unicode_superscript(&inline(s)));
+        if sub_text.as_ref().is_none_or(Option::is_some)
+            && sup_text.as_ref().is_none_or(Option::is_some)
+        {
+            let scripts = format!(
+                "{}{}",
+                sub_text.flatten().unwrap_or_default(),
+                sup_text.flatten().unwrap_or_default()
+            );
+            return Block::beside(&[base_block, Block::text(scripts)]);
+        }
+    }
+
+    // Otherwise raise the superscript above the base and lower the subscript below it
+    let sup_height = sup_block.as_ref().map_or(0, Block::height);
+    let width = sup_block
+        .iter()
+        .chain(sub_block.iter())
+        .map(Block::width)
+        .max()
+        .unwrap_or(0);
+    let mut lines = Vec::new();
+    if let Some(sup) = &sup_block {
+        lines.extend(sup.padded_lines(width));
+    }
+    lines.extend((0..base_block.height()).map(|_| " ".repeat(width)));
+    if let Some(sub) = &sub_block {
+        lines.extend(sub.padded_lines(width));
+    }
+    let scripts = Block {
+        lines,
+        baseline: sup_height + base_block.baseline,
+    };
+    Block::beside(&[base_block, scripts])
+}
+
+fn layout_matrix(env: &str, rows: &[Vec<Expr>]) -> Block {
+    let alignment = is_alignment_env(env);
+    let cells: Vec<Vec<Block>> = rows
+        .iter()
+        .map(|row| row.iter().map(layout).collect())
+        .collect();
+    let columns = cells.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|c| {
+            cells
+                .iter()
+                .filter_map(|row| row.get(c).map(Block::width))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let gap = if alignment { " " } else { "  " };
+    let mut lines = Vec::new();
+    for row in &cells {
+        let mut parts = Vec::new();
+        for (c, width) in widths.iter().enumerate() {
+            if c > 0 {
+                parts.push(Block::text(gap));
+            }
+            let cell = row.get(c).cloned().unwrap_or_else(|| Block::text(""));
+            let lines = if alignment || env == "cases" {
+                cell.padded_lines(*width)
+            } else {
+                cell.centered_lines(*width)
+            };
+            parts.push(Block {
+                lines,
+                baseline: cell.baseline,
+            });
+        }
+        lines.extend(Block::beside(&parts).lines);
+    }
+
+    let body = Block {
+        baseline: lines.len().saturating_sub(1) / 2,
+        lines,
+    };
+    let (open, close) = matrix_delimiters(env);
+    let mut parts = Vec::new();
+    if !open.is_empty() {
+        parts.push(delimiter(open, true, body.height(), body.baseline));
+    }
+    parts.push(body.clone());
+    if !close.is_empty() {
+        parts.push(delimiter(close, false, body.height(), body.baseline));
+    }
+    Block::beside(&parts)
+}
+
+/// A bracket stretched to `height` rows using the Unicode bracket pieces
+fn delimiter(symbol: &str, open: bool, height: usize, baseline: usize) -> Block {
+    if height == 1 {
+        return Block::text(if open {
+            format!("{} ", symbol)
+        } else {
+            format!(" {}", symbol)
+        });
+    }
+
+    let (top, middle, bottom, center) = match symbol {
+        "(" => ("⎛", "⎜", "⎝", "⎜"),
+        ")" => ("⎞", "⎟", "⎠", "⎟"),
+        "[" => ("⎡", "⎢", "⎣", "⎢"),
+        "]" => ("⎤", "⎥", "⎦", "⎥"),
+        "{" => ("⎧", "⎪", "⎩", "⎨"),
+        "}" => ("⎫", "⎪", "⎭", "⎬"),
+        symbol => (symbol, symbol, symbol, symbol),
+    };
+    let lines = (0..height)
+        .map(|row| {
+            let piece = if row == 0 {
+                top
+            } else if row == height - 1 {
+                bottom
+            } else if row == (height - 1) / 2 {
+                center
+            } else {
+                middle
+            };
+            if open {
+                format!("{} ", piece)
+            } else {
+                format!(" {}", piece)
+            }
+        })
+        .collect();
+    Block { lines, baseline }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("\\alpha + \\beta", "α + β")]
+    #[case("x^2 + y_i", "x² + yᵢ")]
+    #[case("e^{ix} = -1", "eⁱˣ = -1")]
+    #[case("e^{i\\pi}", "e^(iπ)")]
+    #[case("\\frac{a}{b}", "a/b")]
+    #[case("\\frac{a+1}{2}", "(a+1)/2")]
+    #[case("\\sqrt{x}", "√x")]
+    #[case("\\sqrt[3]{x+1}", "∛(x+1)")]
+    #[case("\\sum_{i=1}^{n} x_i", "∑ᵢ₌₁ⁿ xᵢ")]
+    #[case("x_{\\mathrm{max}}", "xₘₐₓ")]
+    #[case("x^{Q}", "x^Q")]
+    #[case("\\mathbb{R}^n", "ℝⁿ")]
+    #[case("\\begin{pmatrix} a & b \\\\ c & d \\end{pmatrix}", "(a b; c d)")]
+    #[case("\\text{if } x \\leq 0", "if x ≤ 0")]
+    #[case("\\unknown", "\\unknown")]
+    fn test_render_inline(#[case] source: &str, #[case] expected: &str) {
+        assert_eq!(render_inline(source), expected);
+    }
+
+    #[test]
+    fn test_render_display_frac() {
+        assert_eq!(
+            render_display("\\frac{a+b}{2}"),
+            vec![" a+b", "─────", "  2"]
+        );
+    }
+
+    #[test]
+    fn test_render_display_frac_baseline() {
+        assert_eq!(
+            render_display("y = \\frac{1}{x}"),
+            vec!["     1", "y = ───", "     x"]
+        );
+    }
+
+    #[test]
+    fn test_render_display_sum_limits() {
+        assert_eq!(
+            render_display("\\sum_{i=1}^{n} i"),
+            vec![" n", " ∑  i", "i=1"]
+        );
+    }
+
+    #[test]
+    fn test_render_display_sqrt() {
+        assert_eq!(render_display("\\sqrt{x}"), vec![" _", "√x"]);
+    }
+
+    #[test]
+    fn test_render_display_matrix() {
+        assert_eq!(
+            render_display("\\begin{bmatrix} 1 & 0 \\\\ 0 & 1 \\end{bmatrix}"),
+            vec!["⎡ 1  0 ⎤", "⎣ 0  1 ⎦"]
+        );
+    }
+
+    #[test]
+    fn test_render_display_aligned_rows() {
+        assert_eq!(render_display("a &= b \\\\ c &= d"), vec!["a = b", "c = d"]);
+    }
+}
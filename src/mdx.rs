@@ -0,0 +1,190 @@
+use mq_markdown::{MdxAttributeContent, MdxAttributeValue};
+
+/// MDX components rendered as callouts, with the callout type they default to
+const CALLOUT_COMPONENTS: &[(&str, &str)] = &[
+    ("Callout", "NOTE"),
+    ("Admonition", "NOTE"),
+    ("Aside", "NOTE"),
+    ("Note", "NOTE"),
+    ("Info", "NOTE"),
+    ("Tip", "TIP"),
+    ("Important", "IMPORTANT"),
+    ("Warning", "WARNING"),
+    ("Caution", "CAUTION"),
+    ("Danger", "CAUTION"),
+];
+
+/// Common `type` values of callout components that differ from the GitHub callout names
+const CALLOUT_TYPE_ALIASES: &[(&str, &str)] = &[
+    ("info", "NOTE"),
+    ("default", "NOTE"),
+    ("success", "TIP"),
+    ("warn", "WARNING"),
+    ("error", "CAUTION"),
+    ("danger", "CAUTION"),
+];
+
+/// How a known MDX component is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Component<'a> {
+    /// A callout of the given GitHub callout type, with an optional title
+    Callout {
+        kind: &'a str,
+        title: Option<&'a str>,
+    },
+    /// A group of `<Tab>` children, each shown under its label
+    Tabs,
+    /// A single tab with its label
+    Tab { label: Option<&'a str> },
+    /// A collapsible section with its summary, open when it has an `open` attribute
+    Details {
+        summary: Option<&'a str>,
+        open: bool,
+    },
+    /// Any other component, shown as a labelled box
+    Other,
+}
+
+/// Classify a JSX flow element by its name and attributes
+pub(crate) fn component<'a>(name: &str, attributes: &'a [MdxAttributeContent]) -> Component<'a> {
+    if let Some((_, default_kind)) = CALLOUT_COMPONENTS.iter().find(|(n, _)| *n == name) {
+        let kind = ["type", "kind", "variant", "status"]
+            .iter()
+            .find_map(|attr| attribute(attributes, attr))
+            .map_or(*default_kind, callout_kind);
+        return Component::Callout {
+            kind,
+            title: attribute(attributes, "title"),
+        };
+    }
+
+    match name {
+        "Tabs" => Component::Tabs,
+        "Tab" | "TabItem" => Component::Tab {
+            label: attribute(attributes, "label")
+                .or_else(|| attribute(attributes, "title"))
+                .or_else(|| attribute(attributes, "value")),
+        },
+        "Details" | "Collapsible" | "Accordion" => Component::Details {
+            summary: attribute(attributes, "summary").or_else(|| attribute(attributes, "title")),
+            open: flag(attributes, "open"),
+        },
+        _ => Component::Other,
+    }
+}
+
+/// GitHub callout type for a `type="…"` value, falling back to NOTE for unknown values
+fn callout_kind(value: &str) -> &'static str {
+    const KINDS: &[&str] = &["NOTE", "TIP", "IMPORTANT", "WARNING", "CAUTION"];
+    KINDS
+        .iter()
+        .find(|kind| kind.eq_ignore_ascii_case(value))
+        .copied()
+        .or_else(|| {
+            CALLOUT_TYPE_ALIASES
+                .iter()
+                .find(|(alias, _)| alias.eq_ignore_ascii_case(value))
+                .map(|(_, kind)| *kind)
+        })
+        .unwrap_or("NOTE")
+}
+
+/// Value of a string literal attribute
+pub(crate) fn attribute<'a>(attributes: &'a [MdxAttributeContent], name: &str) -> Option<&'a str> {
+    attributes.iter().find_map(|attribute| match attribute {
+        MdxAttributeContent::Property(property) if property.name == name => match &property.value {
+            Some(MdxAttributeValue::Literal(value)) => Some(value.as_str()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// Whether a boolean attribute is set, as `open` or `open={true}`
+fn flag(attributes: &[MdxAttributeContent], name: &str) -> bool {
+    attributes.iter().any(|attribute| match attribute {
+        MdxAttributeContent::Property(property) if property.name == name => match &property.value {
+            None => true,
+            Some(MdxAttributeValue::Expression(value)) => value.trim() == "true",
+            Some(MdxAttributeValue::Literal(_)) => false,
+        },
+        _ => false,
+    })
+}
+
+/// Format a JSX tag as written: `<Name prop="value" count={3} />`
+pub(crate) fn format_tag(
+    name: &str,
+    attributes: &[MdxAttributeContent],
+    self_closing: bool,
+) -> String {
+    let mut tag = format!("<{}", name);
+    for attribute in attributes {
+        tag.push(' ');
+        match attribute {
+            MdxAttributeContent::Expression(expression) => {
+                tag.push_str(&format!("{{{}}}", expression))
+            }
+            MdxAttributeContent::Property(property) => {
+                tag.push_str(&property.name);
+                match &property.value {
+                    Some(MdxAttributeValue::Literal(value)) => {
+                        tag.push_str(&format!("=\"{}\"", value))
+                    }
+                    Some(MdxAttributeValue::Expression(value)) => {
+                        tag.push_str(&format!("={{{}}}", value))
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+    tag.push_str(if self_closing { " />" } else { ">" });
+    tag
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mq_markdown::{Markdown, Node};
+    use rstest::rstest;
+
+    fn flow_element(source: &str) -> (String, Vec<MdxAttributeContent>) {
+        let markdown = Markdown::from_mdx_str(source).unwrap();
+        match markdown.nodes.first() {
+            Some(Node::MdxJsxFlowElement(element)) => (
+                element.name.as_deref().unwrap_or_default().to_string(),
+                element.attributes.clone(),
+            ),
+            node => panic!("expected a JSX flow element, got {:?}", node),
+        }
+    }
+
+    #[rstest]
+    #[case("<Callout>\nx\n</Callout>", Component::Callout { kind: "NOTE", title: None })]
+    #[case(
+        "<Callout type=\"warn\" title=\"Heads up\">\nx\n</Callout>",
+        Component::Callout { kind: "WARNING", title: Some("Heads up") }
+    )]
+    #[case("<Tip>\nx\n</Tip>", Component::Callout { kind: "TIP", title: None })]
+    #[case("<Tabs>\nx\n</Tabs>", Component::Tabs)]
+    #[case("<Tab label=\"npm\">\nx\n</Tab>", Component::Tab { label: Some("npm") })]
+    #[case("<Details summary=\"More\">\nx\n</Details>", Component::Details { summary: Some("More"), open: false })]
+    #[case("<Details title=\"More\" open>\nx\n</Details>", Component::Details { summary: Some("More"), open: true })]
+    #[case("<Details open={false}>\nx\n</Details>", Component::Details { summary: None, open: false })]
+    #[case("<Chart data={data} />", Component::Other)]
+    fn test_component(#[case] source: &str, #[case] expected: Component) {
+        let (name, attributes) = flow_element(source);
+        assert_eq!(component(&name, &attributes), expected);
+    }
+
+    #[test]
+    fn test_format_tag() {
+        let (name, attributes) =
+            flow_element("<Chart data={points} title=\"Sales\" {...rest} hidden />");
+        assert_eq!(
+            format_tag(&name, &attributes, true),
+            "<Chart data={points} title=\"Sales\" {...rest} hidden />"
+        );
+    }
+}
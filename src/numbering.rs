@@ -0,0 +1,105 @@
+/// How ordered list items are numbered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ListNumbering {
+    /// `1.` at every level
+    #[default]
+    Decimal,
+    /// `1.`, then `a.`, then `i.` for each level of nested ordered lists, repeating
+    ByDepth,
+    /// Nested items continue their parent's number: `1.`, `1.1.`, `1.1.1.`
+    Hierarchical,
+}
+
+/// The number of an item at `ordered_depth` ordered lists deep, without its delimiter
+pub(crate) fn item_number(number: u64, ordered_depth: usize, numbering: ListNumbering) -> String {
+    match numbering {
+        ListNumbering::ByDepth if number > 0 => match ordered_depth % 3 {
+            1 => alphabetic(number),
+            2 => roman(number),
+            _ => number.to_string(),
+        },
+        _ => number.to_string(),
+    }
+}
+
+/// The start number of an ordered list from its first item's source line, such as `3` for
+/// `3. Step`. `column` is the 1-based column of the item marker.
+pub(crate) fn start_number(line: &str, column: usize) -> Option<u64> {
+    let digits: String = line
+        .chars()
+        .skip(column.saturating_sub(1))
+        .take_while(char::is_ascii_digit)
+        .collect();
+    digits.parse().ok()
+}
+
+/// `a` … `z`, then `aa`, `ab`, …
+fn alphabetic(mut number: u64) -> String {
+    let mut letters = Vec::new();
+    while number > 0 {
+        number -= 1;
+        letters.push((b'a' + (number % 26) as u8) as char);
+        number /= 26;
+    }
+    letters.iter().rev().collect()
+}
+
+/// Lowercase roman numerals
+fn roman(mut number: u64) -> String {
+    const NUMERALS: &[(u64, &str)] = &[
+        (1000, "m"),
+        (900, "cm"),
+        (500, "d"),
+        (400, "cd"),
+        (100, "c"),
+        (90, "xc"),
+        (50, "l"),
+        (40, "xl"),
+        (10, "x"),
+        (9, "ix"),
+        (5, "v"),
+        (4, "iv"),
+        (1, "i"),
+    ];
+    let mut numeral = String::new();
+    for (value, symbol) in NUMERALS {
+        while number >= *value {
+            numeral.push_str(symbol);
+            number -= value;
+        }
+    }
+    numeral
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(3, 0, ListNumbering::ByDepth, "3")]
+    #[case(3, 1, ListNumbering::ByDepth, "c")]
+    #[case(28, 1, ListNumbering::ByDepth, "ab")]
+    #[case(4, 2, ListNumbering::ByDepth, "iv")]
+    #[case(1994, 2, ListNumbering::ByDepth, "mcmxciv")]
+    #[case(2, 3, ListNumbering::ByDepth, "2")]
+    #[case(0, 1, ListNumbering::ByDepth, "0")]
+    #[case(3, 1, ListNumbering::Decimal, "3")]
+    fn test_item_number(
+        #[case] number: u64,
+        #[case] ordered_depth: usize,
+        #[case] numbering: ListNumbering,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(item_number(number, ordered_depth, numbering), expected);
+    }
+
+    #[rstest]
+    #[case("3. Step", 1, Some(3))]
+    #[case("0) Zero", 1, Some(0))]
+    #[case("> 12. Quoted", 3, Some(12))]
+    #[case("- bullet", 1, None)]
+    fn test_start_number(#[case] line: &str, #[case] column: usize, #[case] expected: Option<u64>) {
+        assert_eq!(start_number(line, column), expected);
+    }
+}
@@ -1,4 +1,20 @@
+use crate::callout::{
+    self, Admonition, AdmonitionFence, CalloutBox, CalloutMarker, CalloutStyle, Callouts, Fold,
+};
+use crate::definitions::Definitions;
+use crate::diff;
+use crate::footnotes::{self, Footnotes};
+use crate::front_matter::{self, FrontMatter, FrontMatterFormat, FrontMatterMode};
+use crate::heading::{HeadingStyle, large_heading_lines};
 use crate::highlighter::SyntaxHighlighter;
+use crate::html::{self, HtmlBlock};
+use crate::inline_style;
+use crate::math;
+use crate::mdx::{self, Component};
+use crate::numbering::{self, ListNumbering};
+use crate::table::{self, Align, Table};
+use crate::tasks::{self, Progress};
+use crate::width;
 use colored::*;
 use mq_markdown::{Markdown, Node};
 use std::io::{self, Write};
@@ -7,67 +23,160 @@ use std::path::Path;
 /// Unicode header symbols (①②③④⑤⑥)
 const HEADER_SYMBOLS: &[&str] = &["①", "②", "③", "④", "⑤", "⑥"];
 
+/// Layout width used when the terminal width is unknown
+pub const DEFAULT_WIDTH: usize = 80;
+
 /// Unicode bullet symbols for lists
 const LIST_BULLETS: &[&str] = &["●", "○", "◆", "◇"];
 
-/// GitHub-style callout definitions
-#[derive(Debug, Clone)]
-struct Callout {
-    icon: &'static str,
-    color: colored::Color,
-    name: &'static str,
-}
-
-const CALLOUTS: &[(&str, Callout)] = &[
-    (
-        "NOTE",
-        Callout {
-            icon: "ℹ️",
-            color: colored::Color::Blue,
-            name: "Note",
-        },
-    ),
-    (
-        "TIP",
-        Callout {
-            icon: "💡",
-            color: colored::Color::Green,
-            name: "Tip",
-        },
-    ),
-    (
-        "IMPORTANT",
-        Callout {
-            icon: "❗",
-            color: colored::Color::Magenta,
-            name: "Important",
-        },
-    ),
-    (
-        "WARNING",
-        Callout {
-            icon: "⚠️",
-            color: colored::Color::Yellow,
-            name: "Warning",
-        },
-    ),
-    (
-        "CAUTION",
-        Callout {
-            icon: "🔥",
-            color: colored::Color::Red,
-            name: "Caution",
-        },
-    ),
-];
-
 /// Create a clickable link using ANSI escape sequences (OSC 8)
 /// Format: ESC ] 8 ; params ; URI ST display_text ESC ] 8 ; ; ST
-fn make_clickable_link(url: &str, display_text: &str) -> String {
+pub(crate) fn make_clickable_link(url: &str, display_text: &str) -> String {
     // Using ST (String Terminator) \x1b\\ instead of BEL \x07 for better compatibility
     format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, display_text)
 }
 
+/// Options controlling how a Markdown document is rendered.
+///
+/// # Examples
+///
+/// ```rust
+/// use mqv::{RenderOptions, render_markdown_to_string_with_options};
+/// use mq_markdown::Markdown;
+///
+/// let markdown: Markdown = "```rust\nfn main() {\n```".parse().unwrap();
+/// let options = RenderOptions {
+///     mark_syntax_errors: true,
+///     ..Default::default()
+/// };
+/// let rendered = render_markdown_to_string_with_options(&markdown, &options).unwrap();
+/// println!("{}", rendered);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    /// Underline tree-sitter ERROR and MISSING nodes inside fenced code blocks
+    pub mark_syntax_errors: bool,
+    /// Render ```` ```markdown ```` / ```` ```md ```` fences as a live preview instead of source
+    pub preview_markdown: bool,
+    /// How h1 and h2 headings are drawn
+    pub heading_style: HeadingStyle,
+    /// Layout width in terminal columns
+    pub width: usize,
+    /// How YAML/TOML front matter is displayed
+    pub front_matter: FrontMatterMode,
+    /// Show the body of `<details>` sections and folded callouts instead of only their summary
+    pub expand_details: bool,
+    /// Callout types recognized in `> [!TYPE]` blockquotes
+    pub callouts: Callouts,
+    /// How the box around callouts is drawn
+    pub callout_box: CalloutBox,
+    /// How ordered list items are numbered
+    pub list_numbering: ListNumbering,
+    /// Show a progress bar under each task list and a summary for the whole document
+    pub task_progress: bool,
+    /// Right-align table columns without an explicit alignment when all their cells are numbers
+    pub align_numbers: bool,
+    /// Render the Pandoc `^superscript^` / `~subscript~` and `==highlight==` extensions
+    pub inline_extensions: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            mark_syntax_errors: false,
+            preview_markdown: false,
+            heading_style: HeadingStyle::default(),
+            width: DEFAULT_WIDTH,
+            front_matter: FrontMatterMode::default(),
+            expand_details: false,
+            callouts: Callouts::default(),
+            callout_box: CalloutBox::default(),
+            list_numbering: ListNumbering::default(),
+            task_progress: false,
+            align_numbers: false,
+            inline_extensions: false,
+        }
+    }
+}
+
+/// State shared by the render functions while rendering a single document
+struct RenderContext<'a> {
+    highlighter: SyntaxHighlighter,
+    options: &'a RenderOptions,
+    footnotes: Footnotes<'a>,
+    definitions: Definitions<'a>,
+    /// Whether the document has its own h1, so a front matter title is not shown as one
+    has_h1: bool,
+    /// Line prefixes of the enclosing block containers (quote bars, list indents, callout
+    /// borders), outermost first
+    prefixes: Vec<String>,
+    /// The Markdown source, if known, for details the parsed nodes do not keep
+    source: Option<&'a str>,
+    /// Numbers of the enclosing list items, outermost first, with `None` for bullets
+    list_path: Vec<Option<String>>,
+}
+
+impl<'a> RenderContext<'a> {
+    fn new(markdown: &'a Markdown, source: Option<&'a str>, options: &'a RenderOptions) -> Self {
+        Self {
+            highlighter: SyntaxHighlighter::new(),
+            options,
+            footnotes: Footnotes::collect(markdown),
+            definitions: Definitions::collect(markdown),
+            has_h1: markdown
+                .nodes
+                .iter()
+                .any(|node| matches!(node, Node::Heading(heading) if heading.depth == 1)),
+            prefixes: Vec::new(),
+            source,
+            list_path: Vec::new(),
+        }
+    }
+
+    /// Columns left for content inside the prefixes of the enclosing containers
+    fn content_width(&self) -> usize {
+        let prefix_width: usize = self
+            .prefixes
+            .iter()
+            .map(|prefix| width::display_width(prefix))
+            .sum();
+        self.options.width.saturating_sub(prefix_width)
+    }
+
+    /// Run `render` with `prefix` on the prefix stack, taking its columns from the content width
+    fn with_prefix<T>(&mut self, prefix: &str, render: impl FnOnce(&mut Self) -> T) -> T {
+        self.prefixes.push(prefix.to_string());
+        let result = render(self);
+        self.prefixes.pop();
+        result
+    }
+}
+
+/// Render the content of a block container, then write `first` before its first line and
+/// `rest` before every following line. `rest` stays on the context's prefix stack while the
+/// content renders, so nested containers and code blocks lay out in the remaining width.
+fn render_prefixed<W: Write>(
+    first: &str,
+    rest: &str,
+    ctx: &mut RenderContext,
+    writer: &mut W,
+    render: impl FnOnce(&mut RenderContext, &mut Vec<u8>) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut buffer = Vec::new();
+    ctx.with_prefix(rest, |ctx| render(ctx, &mut buffer))?;
+
+    let content = String::from_utf8_lossy(&buffer);
+    for (i, line) in content.trim_matches('\n').split('\n').enumerate() {
+        let prefix = if i == 0 { first } else { rest };
+        if line.is_empty() {
+            writeln!(writer, "{}", prefix.trim_end())?;
+        } else {
+            writeln!(writer, "{}{}", prefix, line)?;
+        }
+    }
+    Ok(())
+}
+
 /// Render a Markdown document to a writer with syntax highlighting and rich text formatting.
 ///
 /// # Errors
@@ -89,30 +198,321 @@ fn make_clickable_link(url: &str, display_text: &str) -> String {
 /// }
 /// ```
 pub fn render_markdown<W: Write>(markdown: &Markdown, writer: &mut W) -> io::Result<()> {
-    let mut highlighter = SyntaxHighlighter::new();
+    render_markdown_with_options(markdown, writer, &RenderOptions::default())
+}
+
+/// Render a Markdown document to a writer using the given [`RenderOptions`].
+///
+/// Without the source, ordered lists count from 1 whatever their first number; use
+/// [`render_markdown_with_source`] to keep start numbers such as `3.`.
+///
+/// # Errors
+///
+/// Returns an `io::Error` if writing to the output fails.
+pub fn render_markdown_with_options<W: Write>(
+    markdown: &Markdown,
+    writer: &mut W,
+    options: &RenderOptions,
+) -> io::Result<()> {
+    render_document(markdown, None, writer, options)
+}
+
+/// Render a Markdown document along with the source it was parsed from.
+///
+/// The source recovers details the parsed nodes do not keep, such as the start number of
+/// ordered lists (`3.`), which otherwise count from 1.
+///
+/// # Errors
+///
+/// Returns an `io::Error` if writing to the output fails.
+///
+/// # Examples
+///
+/// ```rust
+/// use mqv::{RenderOptions, render_markdown_with_source};
+/// use mq_markdown::Markdown;
+///
+/// let source = "3. Third\n4. Fourth";
+/// let markdown: Markdown = source.parse().unwrap();
+/// let mut output = Vec::new();
+/// render_markdown_with_source(&markdown, source, &mut output, &RenderOptions::default()).unwrap();
+/// ```
+pub fn render_markdown_with_source<W: Write>(
+    markdown: &Markdown,
+    source: &str,
+    writer: &mut W,
+    options: &RenderOptions,
+) -> io::Result<()> {
+    render_document(markdown, Some(source), writer, options)
+}
+
+fn render_document<W: Write>(
+    markdown: &Markdown,
+    source: Option<&str>,
+    writer: &mut W,
+    options: &RenderOptions,
+) -> io::Result<()> {
+    let mut ctx = RenderContext::new(markdown, source, options);
+    render_document_nodes(markdown, &mut ctx, writer)
+}
+
+/// Render a document's nodes, then its task summary and footnotes, with a context created for it
+fn render_document_nodes<W: Write>(
+    markdown: &Markdown,
+    ctx: &mut RenderContext,
+    writer: &mut W,
+) -> io::Result<()> {
+    render_nodes(&markdown.nodes, 0, ctx, writer)?;
+    if ctx.options.task_progress {
+        render_task_summary(markdown, writer)?;
+    }
+    render_footnotes_section(ctx, writer)?;
+    Ok(())
+}
+
+/// Render sibling block nodes, grouping table cells into tables and the markdown between
+/// `<details>` and `</details>` blocks into collapsible sections
+fn render_nodes<W: Write>(
+    nodes: &[Node],
+    depth: usize,
+    ctx: &mut RenderContext,
+    writer: &mut W,
+) -> io::Result<()> {
     let mut i = 0;
-    let len = markdown.nodes.len();
+    while i < nodes.len() {
+        i = render_block(nodes, i, depth, ctx, writer)?;
+    }
+    Ok(())
+}
 
-    while i < len {
-        let node = &markdown.nodes[i];
-        if matches!(node, Node::TableCell(_)) {
-            // Collect consecutive table-related nodes
-            let table_nodes: Vec<&Node> = markdown.nodes[i..]
-                .iter()
-                .take_while(|n| {
-                    matches!(
-                        n,
-                        Node::TableCell(_) | Node::TableHeader(_) | Node::TableRow(_)
-                    )
-                })
-                .collect();
-            render_table(&table_nodes, &mut highlighter, writer)?;
-            i += table_nodes.len();
-        } else {
-            render_node(node, 0, &mut highlighter, writer)?;
-            i += 1;
+/// Render sibling block nodes like [`render_nodes`], with a blank line between blocks, as in
+/// the items of loose lists
+fn render_nodes_separated<W: Write>(
+    nodes: &[Node],
+    depth: usize,
+    ctx: &mut RenderContext,
+    writer: &mut W,
+) -> io::Result<()> {
+    let mut i = 0;
+    let mut first = true;
+    while i < nodes.len() {
+        let mut buffer = Vec::new();
+        i = render_block(nodes, i, depth, ctx, &mut buffer)?;
+        let block = String::from_utf8_lossy(&buffer);
+        let block = block.trim_matches('\n');
+        if block.is_empty() {
+            continue;
+        }
+        if !first {
+            writeln!(writer)?;
+        }
+        writeln!(writer, "{}", block)?;
+        first = false;
+    }
+    Ok(())
+}
+
+/// Render the block starting at `nodes[i]`, which may span several sibling nodes, and return
+/// the index of the node after it
+fn render_block<W: Write>(
+    nodes: &[Node],
+    i: usize,
+    depth: usize,
+    ctx: &mut RenderContext,
+    writer: &mut W,
+) -> io::Result<usize> {
+    let node = &nodes[i];
+    if matches!(node, Node::TableCell(_)) {
+        // Collect the table nodes of this table; a cell in row 0, column 0 starts the next one
+        let len = nodes[i..]
+            .iter()
+            .enumerate()
+            .take_while(|(offset, node)| match node {
+                Node::TableCell(cell) => *offset == 0 || cell.row > 0 || cell.column > 0,
+                Node::TableHeader(_) | Node::TableRow(_) => true,
+                _ => false,
+            })
+            .count();
+        render_table(&Table::from_nodes(&nodes[i..i + len]), ctx, writer)?;
+        Ok(i + len)
+    } else if matches!(node, Node::List(_)) {
+        let items = list_items(&nodes[i..]);
+        render_list(&items, depth, ctx, writer)?;
+        Ok(i + items.len())
+    } else if let Some((admonition, body, end)) = admonition(nodes, i, ctx.options) {
+        // Indented bodies are re-parsed from code blocks, so their positions do not index
+        // the document source
+        let source = match admonition.fence {
+            AdmonitionFence::Indented => ctx.source.take(),
+            AdmonitionFence::Colons(_) => ctx.source,
+        };
+        writeln!(writer)?;
+        let result = render_callout(
+            admonition.style,
+            admonition.title,
+            admonition.fold,
+            &body,
+            depth,
+            ctx,
+            writer,
+        );
+        ctx.source = source;
+        result?;
+        writeln!(writer)?;
+        Ok(end)
+    } else if let Node::Html(html) = node
+        && let Some(details) = html::details_start(&html.value)
+    {
+        let end = details_end(nodes, i + 1);
+        render_details(&details, &nodes[i + 1..end], depth, ctx, writer)?;
+        Ok(end + 1)
+    } else {
+        render_node(node, depth, ctx, writer)?;
+        Ok(i + 1)
+    }
+}
+
+/// Recognize a MkDocs or Docusaurus admonition opening at `nodes[start]`, returning it with its
+/// body and the index of the first node after it.
+///
+/// MkDocs bodies are the paragraph's continuation lines and the indented code blocks that follow,
+/// parsed as markdown. Docusaurus bodies run to a closing `:::` line; without one the opening
+/// paragraph is left as text.
+fn admonition<'a>(
+    nodes: &'a [Node],
+    start: usize,
+    options: &'a RenderOptions,
+) -> Option<(Admonition<'a>, Vec<Node>, usize)> {
+    let Node::Fragment(para) = &nodes[start] else {
+        return None;
+    };
+    let Some(Node::Text(text)) = para.values.first() else {
+        return None;
+    };
+    let line = text.value.split('\n').next().unwrap_or_default();
+    let admonition = options.callouts.detect_admonition(line)?;
+    let rest = without_first_line(para);
+
+    match admonition.fence {
+        AdmonitionFence::Indented => {
+            let mut body: Vec<Node> = rest.into_iter().collect();
+            let mut end = start + 1;
+            while let Some(Node::Code(code)) = nodes.get(end)
+                && is_indented_code(code)
+            {
+                if let Ok(markdown) = code.value.parse::<Markdown>() {
+                    body.extend(markdown.nodes);
+                }
+                end += 1;
+            }
+            Some((admonition, body, end))
+        }
+        AdmonitionFence::Colons(colons) => {
+            let mut body = Vec::new();
+            for (index, node) in rest.iter().chain(&nodes[start + 1..]).enumerate() {
+                match split_closing_fence(node, colons) {
+                    Some(before) => {
+                        body.extend(before);
+                        let end = start + 1 + index + usize::from(rest.is_none());
+                        return Some((admonition, body, end));
+                    }
+                    None => body.push(node.clone()),
+                }
+            }
+            None
+        }
+    }
+}
+
+/// A paragraph without its first line, or `None` if nothing is left
+fn without_first_line(para: &mq_markdown::Fragment) -> Option<Node> {
+    let mut para = para.clone();
+    if let Some(Node::Text(text)) = para.values.first_mut() {
+        text.value = text
+            .value
+            .split_once('\n')
+            .map_or(String::new(), |(_, rest)| rest.to_string());
+        if text.value.is_empty() {
+            para.values.remove(0);
+        }
+    }
+    (!para.values.is_empty()).then_some(Node::Fragment(para))
+}
+
+/// If a paragraph ends with a `:::` closing line, the paragraph without it. The closing line
+/// can also end the last item of a list, as a lazy continuation of its paragraph.
+fn split_closing_fence(node: &Node, colons: usize) -> Option<Option<Node>> {
+    let para = match node {
+        Node::Fragment(para) => para,
+        Node::List(list) => {
+            let mut list = list.clone();
+            let last = split_closing_fence(list.values.last()?, colons)?;
+            list.values.pop();
+            list.values.extend(last);
+            return Some(Some(Node::List(list)));
+        }
+        _ => return None,
+    };
+    let Some(Node::Text(text)) = para.values.last() else {
+        return None;
+    };
+    let (before, last_line) = text.value.rsplit_once('\n').unwrap_or(("", &text.value));
+    if !callout::is_closing_fence(last_line, colons) {
+        return None;
+    }
+
+    let mut para = para.clone();
+    let before = before.to_string();
+    if let Some(Node::Text(text)) = para.values.last_mut() {
+        text.value = before;
+        if text.value.is_empty() {
+            para.values.pop();
+        }
+    }
+    Some((!para.values.is_empty()).then_some(Node::Fragment(para)))
+}
+
+/// Whether a code block can be an indented MkDocs admonition body, which has no info string
+fn is_indented_code(code: &mq_markdown::Code) -> bool {
+    code.lang.is_none() && code.meta.is_none()
+}
+
+/// Index of the `</details>` block closing a section whose body starts at `start`, skipping
+/// nested sections; an unclosed section runs to the end
+fn details_end(nodes: &[Node], start: usize) -> usize {
+    let mut open = 0;
+    for (i, node) in nodes.iter().enumerate().skip(start) {
+        if let Node::Html(html) = node {
+            if html::details_start(&html.value).is_some() {
+                open += 1;
+            } else if html::is_details_end(&html.value) {
+                if open == 0 {
+                    return i;
+                }
+                open -= 1;
+            }
         }
     }
+    nodes.len()
+}
+
+/// Render a `▶ summary` line, followed by the indented body when the section is expanded
+fn render_details<W: Write>(
+    details: &html::DetailsStart,
+    body: &[Node],
+    depth: usize,
+    ctx: &mut RenderContext,
+    writer: &mut W,
+) -> io::Result<()> {
+    let expanded = details.open || ctx.options.expand_details;
+    writeln!(writer, "{}", html::summary_line(&details.summary, expanded))?;
+    if expanded && !(details.body.is_empty() && body.is_empty()) {
+        render_prefixed("  ", "  ", ctx, writer, |ctx, buffer| {
+            render_html_blocks(&details.body, ctx, buffer)?;
+            render_nodes(body, depth, ctx, buffer)
+        })?;
+    }
+    writeln!(writer)?;
     Ok(())
 }
 
@@ -129,197 +529,212 @@ pub fn render_markdown<W: Write>(markdown: &Markdown, writer: &mut W) -> io::Res
 /// println!("{}", rendered);
 /// ```
 pub fn render_markdown_to_string(markdown: &Markdown) -> io::Result<String> {
+    render_markdown_to_string_with_options(markdown, &RenderOptions::default())
+}
+
+/// Render a Markdown document to a String using the given [`RenderOptions`].
+///
+/// Like [`render_markdown_with_options`], ordered lists count from 1.
+pub fn render_markdown_to_string_with_options(
+    markdown: &Markdown,
+    options: &RenderOptions,
+) -> io::Result<String> {
     let mut output = Vec::new();
-    render_markdown(markdown, &mut output)?;
+    render_markdown_with_options(markdown, &mut output, options)?;
     String::from_utf8(output).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
 }
 
-fn detect_callout(text: &str) -> Option<&'static Callout> {
-    let trimmed = text.trim();
-    if trimmed.starts_with("[!") && trimmed.contains(']') {
-        if let Some(end) = trimmed.find(']') {
-            let callout_type = &trimmed[2..end];
-            return CALLOUTS
-                .iter()
-                .find(|(name, _)| name.eq_ignore_ascii_case(callout_type))
-                .map(|(_, callout)| callout);
-        }
-    }
-    None
+/// The `[!TYPE]` marker opening the first paragraph of a blockquote, if it is a callout
+fn detect_callout<'a>(
+    blockquote: &'a mq_markdown::Blockquote,
+    callouts: &'a Callouts,
+) -> Option<CalloutMarker<'a>> {
+    let text = match blockquote.values.first()? {
+        Node::Fragment(para) => match para.values.first()? {
+            Node::Text(text) => text,
+            _ => return None,
+        },
+        Node::Text(text) => text,
+        _ => return None,
+    };
+    callouts.detect(&text.value)
 }
 
 fn render_node<W: Write>(
     node: &Node,
     depth: usize,
-    highlighter: &mut SyntaxHighlighter,
+    ctx: &mut RenderContext,
     writer: &mut W,
 ) -> io::Result<()> {
-    render_node_inline(node, depth, false, highlighter, writer)
+    render_node_inline(node, depth, false, ctx, writer)
 }
 
 fn render_node_inline<W: Write>(
     node: &Node,
     depth: usize,
     inline: bool,
-    highlighter: &mut SyntaxHighlighter,
+    ctx: &mut RenderContext,
     writer: &mut W,
 ) -> io::Result<()> {
     match node {
         Node::Heading(heading) => {
-            if !inline {
-                writeln!(writer)?;
-            }
+            let text = render_inline_content(&heading.values, ctx);
+            render_heading(heading.depth, &text, inline, ctx, writer)?;
+        }
 
-            let symbol = HEADER_SYMBOLS
-                .get((heading.depth - 1) as usize)
-                .unwrap_or(&"⑥");
-
-            let text = render_inline_content(&heading.values);
-
-            // Fallback: Use decorative elements to simulate size differences
-            match heading.depth {
-                1 => {
-                    // h1: Largest - double lines above and below with large text
-                    let line = "═".repeat(text.chars().count() + 4);
-                    writeln!(writer, "{}", line.bright_blue())?;
-                    writeln!(
-                        writer,
-                        "{} {}",
-                        symbol.bold().bright_blue(),
-                        text.bold().bright_blue(),
-                    )?;
-                    writeln!(writer, "{}", line.bright_blue())?;
-                }
-                2 => {
-                    // h2: Large - single line below
-                    writeln!(writer, "{} {}", symbol.bold().cyan(), text.bold().cyan())?;
-                    let line = "─".repeat(text.chars().count() + 4);
-                    writeln!(writer, "{}", line.cyan())?;
-                }
-                3 => {
-                    // h3: Medium - double symbol
-                    writeln!(
-                        writer,
-                        "{} {}",
-                        symbol.bold().yellow(),
-                        text.bold().yellow()
-                    )?;
-                }
-                4 => {
-                    // h4: Regular with extra spacing
-                    writeln!(writer, "{} {}", symbol.bold().green(), text.bold().green())?;
-                }
-                5 => {
-                    writeln!(
-                        writer,
-                        "{} {}",
-                        symbol.bold().magenta(),
-                        text.bold().magenta()
-                    )?;
-                }
-                _ => {
-                    writeln!(writer, "{} {}", symbol.bold().white(), text.bold().white())?;
-                }
-            }
-            writeln!(writer)?;
+        Node::Yaml(yaml) => {
+            render_front_matter(&yaml.value, FrontMatterFormat::Yaml, ctx, writer)?;
+        }
+
+        Node::Toml(toml) => {
+            render_front_matter(&toml.value, FrontMatterFormat::Toml, ctx, writer)?;
         }
 
         Node::Text(text) => {
             if !text.value.trim().is_empty() {
+                let text = style_text(&text.value, ctx);
                 if inline {
-                    write!(writer, "{}", text.value)?;
+                    write!(writer, "{}", text)?;
                 } else {
-                    writeln!(writer, "{}", text.value)?;
+                    writeln!(writer, "{}", text)?;
                 }
             }
         }
 
         Node::List(list) => {
-            render_list(list, depth, highlighter, writer)?;
+            render_list(&[list], depth, ctx, writer)?;
         }
 
-        Node::Code(code) => {
-            write!(writer, "{}", "```".bright_black())?;
-            if let Some(lang) = &code.lang {
-                write!(writer, "{}", lang.bright_black())?;
+        Node::Code(code)
+            if ctx.options.preview_markdown
+                && code.lang.as_deref().is_some_and(is_markdown_language) =>
+        {
+            match code.value.parse::<Markdown>() {
+                Ok(markdown) => render_markdown_preview(&markdown, &code.value, ctx, writer)?,
+                Err(_) => render_code_block(code, ctx, writer)?,
             }
-            writeln!(writer)?;
-
-            // Apply syntax highlighting if language is specified
-            let highlighted = highlighter.highlight(&code.value, code.lang.as_deref());
-            write!(writer, "{}", highlighted)?;
+        }
 
-            writeln!(writer)?;
-            writeln!(writer, "{}", "```".bright_black())?;
-            writeln!(writer)?;
+        Node::Code(code) => {
+            render_code_block(code, ctx, writer)?;
         }
 
         Node::CodeInline(code) => {
-            write!(writer, "{}", format!("`{}`", code.value).bright_yellow())?;
+            let (value, lang) = split_inline_code_lang(code.value.as_str());
+            write!(writer, "{}", render_code_inline(value, lang, ctx))?;
+        }
+
+        Node::Math(math) => {
+            render_display_math(&math.value, ctx, writer)?;
+        }
+
+        Node::MathInline(math) => {
+            write!(
+                writer,
+                "{}",
+                math::render_inline(math.value.as_str()).italic()
+            )?;
         }
 
         Node::Strong(strong) => {
-            write!(writer, "{}", render_inline_content(&strong.values).bold())?;
+            write!(
+                writer,
+                "{}",
+                render_inline_content(&strong.values, ctx).bold()
+            )?;
         }
 
         Node::Emphasis(emphasis) => {
             write!(
                 writer,
                 "{}",
-                render_inline_content(&emphasis.values).italic()
+                render_inline_content(&emphasis.values, ctx).italic()
             )?;
         }
 
+        Node::Delete(delete) => {
+            write!(writer, "{}", render_delete(delete, ctx))?;
+        }
+
         Node::Link(link) => {
-            let text = render_inline_content(&link.values);
-            let url = link.url.as_str();
+            let text = render_inline_content(&link.values, ctx);
+            render_link(&text, link.url.as_str(), writer)?;
+        }
 
-            if text.trim().is_empty() {
-                // If no link text, just make the URL clickable
-                write!(
-                    writer,
-                    " {} {}",
-                    "🔗".bright_blue(),
-                    make_clickable_link(url, url)
-                )?;
-            } else {
-                // Make the title clickable without showing URL
-                write!(
-                    writer,
-                    " {} {}",
-                    "🔗".bright_blue(),
-                    make_clickable_link(url, &text).underline().bright_blue()
-                )?;
+        Node::LinkRef(link) => {
+            let text = render_inline_content(&link.values, ctx);
+            match ctx.definitions.url(&link.ident) {
+                Some(url) => render_link(&text, url, writer)?,
+                None => write!(writer, "[{}]", text)?,
             }
         }
 
         Node::Image(image) => {
-            let alt = image.alt.as_str();
-            let url = image.url.as_str();
+            render_image(image.alt.as_str(), image.url.as_str(), writer)?;
+        }
 
-            let _ = render_image_to_terminal(url);
+        Node::ImageRef(image) => match ctx.definitions.url(&image.ident) {
+            Some(url) => render_image(&image.alt, url, writer)?,
+            None => write!(writer, "![{}]", image.alt)?,
+        },
 
-            // Always show the text description as well
-            if alt.trim().is_empty() {
-                writeln!(
-                    writer,
-                    "{} {}",
-                    "🖼️ ".bright_green(),
-                    url.underline().bright_green()
-                )?;
+        Node::MdxJsxFlowElement(element) => {
+            render_jsx_flow_element(element, depth, ctx, writer)?;
+        }
+
+        Node::MdxJsxTextElement(element) => {
+            if element.children.is_empty() {
+                let name = element.name.as_deref().unwrap_or_default();
+                let tag = mdx::format_tag(name, &element.attributes, true);
+                write!(writer, "{}", tag.bright_black())?;
             } else {
-                writeln!(
-                    writer,
-                    "{} {} ({})",
-                    "🖼️ ".bright_green(),
-                    alt.bright_green(),
-                    url.bright_black()
-                )?;
+                render_inline_nodes(&element.children, depth, ctx, writer)?;
             }
         }
 
+        Node::MdxFlowExpression(expression) => {
+            writeln!(
+                writer,
+                "{}",
+                format!("{{{}}}", expression.value).bright_black()
+            )?;
+        }
+
+        Node::MdxTextExpression(expression) => {
+            write!(
+                writer,
+                "{}",
+                format!("{{{}}}", expression.value).bright_black()
+            )?;
+        }
+
+        Node::MdxJsEsm(_) => {
+            // import/export statements have no visible output
+        }
+
+        Node::Definition(_) => {
+            // Definitions are resolved into the references that use them
+        }
+
+        Node::FootnoteRef(reference) => match ctx.footnotes.number(&reference.ident) {
+            Some(number) => write!(
+                writer,
+                "{}",
+                make_clickable_link(
+                    &footnotes::definition_anchor(number),
+                    &footnotes::superscript(number)
+                )
+                .bright_cyan()
+            )?,
+            None => write!(writer, "[^{}]", reference.ident)?,
+        },
+
+        Node::Footnote(_) => {
+            // Definitions are gathered into the footnotes section at the end of the document
+        }
+
         Node::HorizontalRule(_) => {
-            writeln!(writer, "{}", "─".repeat(80).bright_black())?;
+            writeln!(writer, "{}", "─".repeat(ctx.content_width()).bright_black())?;
             writeln!(writer)?;
         }
 
@@ -328,51 +743,34 @@ fn render_node_inline<W: Write>(
                 writeln!(writer)?;
             }
 
-            // Check if this is a GitHub-style callout
-            let is_callout = {
-                let mut found_callout = false;
-                // Check all nodes in blockquote for callout pattern
-                for value in &blockquote.values {
-                    match value {
-                        Node::Fragment(para) => {
-                            for child in &para.values {
-                                if let Node::Text(text) = child {
-                                    if detect_callout(&text.value).is_some() {
-                                        found_callout = true;
-                                        break;
-                                    }
-                                }
-                            }
-                        }
-                        Node::Text(text) => {
-                            if detect_callout(&text.value).is_some() {
-                                found_callout = true;
-                                break;
-                            }
-                        }
-                        _ => {}
-                    }
-                    if found_callout {
-                        break;
-                    }
-                }
-                found_callout
-            };
-
-            if is_callout {
-                render_callout_blockquote(blockquote, depth, highlighter, writer)?;
+            // Check if this is a GitHub or Obsidian-style callout
+            let options = ctx.options;
+            if let Some(marker) = detect_callout(blockquote, &options.callouts) {
+                render_callout_blockquote(blockquote, &marker, depth, ctx, writer)?;
             } else {
-                render_regular_blockquote(blockquote, depth, highlighter, writer)?;
+                render_regular_blockquote(blockquote, depth, ctx, writer)?;
             }
 
             writeln!(writer)?;
         }
 
-        Node::Html(html) => {
-            // Apply syntax highlighting to HTML
-            let highlighted = highlighter.highlight(&html.value, Some("html"));
-            writeln!(writer, "{}", highlighted)?;
-        }
+        Node::Html(html) if inline => match html::interpret_inline(&html.value) {
+            Some(text) => write!(writer, "{}", text)?,
+            None => write!(
+                writer,
+                "{}",
+                ctx.highlighter.highlight(&html.value, Some("html"))
+            )?,
+        },
+
+        Node::Html(html) => match html::interpret_block(&html.value, ctx.options.expand_details) {
+            Some(blocks) => render_html_blocks(&blocks, ctx, writer)?,
+            None => {
+                // Apply syntax highlighting to HTML the interpreter does not understand
+                let highlighted = ctx.highlighter.highlight(&html.value, Some("html"));
+                writeln!(writer, "{}", highlighted)?;
+            }
+        },
 
         Node::Break(_) => {
             if inline {
@@ -384,32 +782,27 @@ fn render_node_inline<W: Write>(
 
         Node::Fragment(fragment) => {
             // Render paragraph as inline content on one line
-            for child in &fragment.values {
-                render_node_inline(child, depth, true, highlighter, writer)?;
-            }
+            render_inline_nodes(&fragment.values, depth, ctx, writer)?;
             // Add newline after paragraph unless we're inline
             if !inline {
                 writeln!(writer)?;
             }
         }
 
-        Node::TableHeader(_) | Node::TableRow(_) => {
-            // These should be handled by render_table in render_markdown
-            // If we encounter them here, skip them
+        Node::TableHeader(_) => {
+            // Only meaningful as part of a table, which render_block groups
         }
 
-        Node::TableCell(cell) => {
-            // Individual table cells outside of tables
-            // Calculate column widths for this cell
-            let column_widths = calculate_column_widths(&[Node::TableCell(cell.clone())]);
-            render_table_cell(cell, &column_widths, highlighter, writer)?;
+        Node::TableCell(_) | Node::TableRow(_) => {
+            // Table nodes outside of a table render as a table of their own
+            render_table(&Table::from_nodes([node]), ctx, writer)?;
         }
 
         // Handle other node types recursively if they have children
         _ => {
             if let Some(children) = get_node_children(node) {
                 for child in children {
-                    render_node_inline(child, depth, inline, highlighter, writer)?;
+                    render_node_inline(child, depth, inline, ctx, writer)?;
                 }
             }
         }
@@ -418,474 +811,1101 @@ fn render_node_inline<W: Write>(
     Ok(())
 }
 
-fn render_list<W: Write>(
-    list: &mq_markdown::List,
+/// Render a link as a clickable OSC 8 hyperlink showing its text, or the URL if it has none
+fn render_link<W: Write>(text: &str, url: &str, writer: &mut W) -> io::Result<()> {
+    if text.trim().is_empty() {
+        // If no link text, just make the URL clickable
+        write!(
+            writer,
+            " {} {}",
+            "🔗".bright_blue(),
+            make_clickable_link(url, url)
+        )
+    } else {
+        // Make the title clickable without showing URL
+        write!(
+            writer,
+            " {} {}",
+            "🔗".bright_blue(),
+            make_clickable_link(url, text).underline().bright_blue()
+        )
+    }
+}
+
+/// Render an image inline in the terminal, followed by its description
+fn render_image<W: Write>(alt: &str, url: &str, writer: &mut W) -> io::Result<()> {
+    let _ = render_image_to_terminal(url);
+
+    // Always show the text description as well
+    if alt.trim().is_empty() {
+        writeln!(
+            writer,
+            "{} {}",
+            "🖼️ ".bright_green(),
+            url.underline().bright_green()
+        )?;
+    } else {
+        writeln!(
+            writer,
+            "{} {} ({})",
+            "🖼️ ".bright_green(),
+            alt.bright_green(),
+            url.bright_black()
+        )?;
+    }
+    Ok(())
+}
+
+/// Render an MDX component: known components map to callouts, tabs and details, and any
+/// other component is shown as a box labelled with its tag
+fn render_jsx_flow_element<W: Write>(
+    element: &mq_markdown::MdxJsxFlowElement,
     depth: usize,
-    highlighter: &mut SyntaxHighlighter,
+    ctx: &mut RenderContext,
     writer: &mut W,
 ) -> io::Result<()> {
-    let indent = "  ".repeat(depth);
-    let bullet_index = depth % LIST_BULLETS.len();
-    let bullet = if list.ordered {
-        format!("{}.", list.index + 1)
-    } else {
-        LIST_BULLETS[bullet_index].to_string()
-    };
-
-    // Handle checkbox lists
-    let checkbox = match list.checked {
-        Some(true) => "☑️ ",
-        Some(false) => "☐ ",
-        None => "",
+    let Some(name) = element.name.as_deref() else {
+        // Fragments (`<>...</>`) only group their children
+        for child in &element.children {
+            render_node(child, depth, ctx, writer)?;
+        }
+        return Ok(());
     };
 
-    write!(writer, "{}{} {}", indent, bullet.bright_magenta(), checkbox)?;
-
-    let mut has_content = false;
-    for value in &list.values {
-        match value {
-            Node::List(nested_list) => {
-                if has_content {
-                    writeln!(writer)?; // New line before nested list only if we had content
-                }
-                render_list(nested_list, depth + 1, highlighter, writer)?;
-            }
-            Node::Fragment(fragment) => {
-                // Handle paragraph content inline
-                for child in &fragment.values {
-                    render_node_inline(child, depth + 1, true, highlighter, writer)?;
+    match mdx::component(name, &element.attributes) {
+        Component::Callout { kind, title } => {
+            let options = ctx.options;
+            match options.callouts.get(kind) {
+                Some(style) => {
+                    render_callout(style, title, None, &element.children, depth, ctx, writer)?
                 }
-                has_content = true;
+                None => render_boxed(
+                    &title.unwrap_or(kind).bold().to_string(),
+                    &element.children,
+                    depth,
+                    ctx,
+                    writer,
+                )?,
             }
-            _ => {
-                render_node_inline(value, depth + 1, true, highlighter, writer)?;
-                has_content = true;
+            writeln!(writer)?;
+        }
+        Component::Tabs => {
+            render_boxed(
+                &"Tabs".bold().to_string(),
+                &element.children,
+                depth,
+                ctx,
+                writer,
+            )?;
+            writeln!(writer)?;
+        }
+        Component::Tab { label } => {
+            writeln!(writer, "{} {}", "▸".cyan(), label.unwrap_or(name).bold())?;
+            for child in &element.children {
+                render_node(child, depth, ctx, writer)?;
             }
         }
+        Component::Details { summary, open } => {
+            let details = html::DetailsStart {
+                summary: summary.unwrap_or("Details").to_string(),
+                open,
+                body: Vec::new(),
+            };
+            render_details(&details, &element.children, depth, ctx, writer)?;
+        }
+        Component::Other => {
+            let tag = mdx::format_tag(name, &element.attributes, element.children.is_empty());
+            render_boxed(
+                &tag.cyan().to_string(),
+                &element.children,
+                depth,
+                ctx,
+                writer,
+            )?;
+            writeln!(writer)?;
+        }
     }
+    Ok(())
+}
 
-    writeln!(writer)?; // Add line break after list item
+/// Render nodes inside a `┌─ header` / `│` / `└─` box, like callouts
+fn render_boxed<W: Write>(
+    header: &str,
+    children: &[Node],
+    depth: usize,
+    ctx: &mut RenderContext,
+    writer: &mut W,
+) -> io::Result<()> {
+    let mut buffer = Vec::new();
+    if !children.is_empty() {
+        // The right border of a closed box, ` │`, takes columns like a prefix
+        let right_border = match ctx.options.callout_box {
+            CalloutBox::Open => "",
+            CalloutBox::Fit | CalloutBox::Full => " │",
+        };
+        ctx.with_prefix(right_border, |ctx| {
+            render_prefixed("│ ", "│ ", ctx, &mut buffer, |ctx, buffer| {
+                render_nodes(children, depth, ctx, buffer)
+            })
+        })?;
+    }
+
+    let body = String::from_utf8_lossy(&buffer);
+    let width = ctx.content_width();
+    for line in callout::box_lines(header, &body, ctx.options.callout_box, width) {
+        writeln!(writer, "{}", line)?;
+    }
     Ok(())
 }
 
-fn render_callout_blockquote<W: Write>(
-    blockquote: &mq_markdown::Blockquote,
-    _depth: usize,
-    highlighter: &mut SyntaxHighlighter,
+/// Render a heading in the configured heading style
+fn render_heading<W: Write>(
+    depth: u8,
+    text: &str,
+    inline: bool,
+    ctx: &RenderContext,
     writer: &mut W,
 ) -> io::Result<()> {
-    // Find the callout type from any text node in the blockquote
-    let mut callout_info = None;
-    let mut callout_text = String::new();
-
-    for value in &blockquote.values {
-        match value {
-            Node::Fragment(para) => {
-                for child in &para.values {
-                    if let Node::Text(text) = child {
-                        if let Some(callout) = detect_callout(&text.value) {
-                            callout_info = Some(callout);
-                            // Extract content after the callout marker
-                            if let Some(end) = text.value.find(']') {
-                                callout_text = text.value[end + 1..].trim_start().to_string();
-                            }
-                            break;
-                        }
-                    }
-                }
+    if !inline {
+        writeln!(writer)?;
+    }
+
+    let symbol = HEADER_SYMBOLS.get((depth - 1) as usize).unwrap_or(&"⑥");
+
+    if let Some(lines) = large_heading_lines(
+        ctx.options.heading_style,
+        depth,
+        symbol,
+        text,
+        ctx.content_width(),
+    ) {
+        for line in lines {
+            let line = if depth == 1 {
+                line.bold().bright_blue()
+            } else {
+                line.bold().cyan()
+            };
+            writeln!(writer, "{}", line)?;
+        }
+    } else {
+        // Fallback: Use decorative elements to simulate size differences
+        match depth {
+            1 => {
+                // h1: Largest - double lines above and below with large text
+                let line = "═".repeat(width::display_width(text) + 4);
+                writeln!(writer, "{}", line.bright_blue())?;
+                writeln!(
+                    writer,
+                    "{} {}",
+                    symbol.bold().bright_blue(),
+                    text.bold().bright_blue(),
+                )?;
+                writeln!(writer, "{}", line.bright_blue())?;
             }
-            Node::Text(text) => {
-                if let Some(callout) = detect_callout(&text.value) {
-                    callout_info = Some(callout);
-                    if let Some(end) = text.value.find(']') {
-                        callout_text = text.value[end + 1..].trim_start().to_string();
-                    }
-                    break;
-                }
+            2 => {
+                // h2: Large - single line below
+                writeln!(writer, "{} {}", symbol.bold().cyan(), text.bold().cyan())?;
+                let line = "─".repeat(width::display_width(text) + 4);
+                writeln!(writer, "{}", line.cyan())?;
+            }
+            3 => {
+                // h3: Medium - double symbol
+                writeln!(
+                    writer,
+                    "{} {}",
+                    symbol.bold().yellow(),
+                    text.bold().yellow()
+                )?;
+            }
+            4 => {
+                // h4: Regular with extra spacing
+                writeln!(writer, "{} {}", symbol.bold().green(), text.bold().green())?;
+            }
+            5 => {
+                writeln!(
+                    writer,
+                    "{} {}",
+                    symbol.bold().magenta(),
+                    text.bold().magenta()
+                )?;
+            }
+            _ => {
+                writeln!(writer, "{} {}", symbol.bold().white(), text.bold().white())?;
             }
-            _ => {}
         }
-        if callout_info.is_some() {
-            break;
-        }
-    }
-
-    if let Some(callout) = callout_info {
-        // Print the callout header
-        let header = format!("{} {}", callout.icon, callout.name)
-            .color(callout.color)
-            .bold();
-        writeln!(writer, "┌─ {}", header)?;
-
-        // Print the content
-        if !callout_text.is_empty() {
-            writeln!(writer, "│ {}", callout_text)?;
-        }
-
-        // Print remaining content from blockquote
-        let mut found_callout_marker = false;
-        for value in &blockquote.values {
-            match value {
-                Node::Fragment(para) => {
-                    let mut line_content = String::new();
-                    for child in &para.values {
-                        match child {
-                            Node::Text(text) => {
-                                if !found_callout_marker && detect_callout(&text.value).is_some() {
-                                    found_callout_marker = true;
-                                    // Skip the callout marker part
-                                    if let Some(end) = text.value.find(']') {
-                                        let remaining = text.value[end + 1..].trim_start();
-                                        if !remaining.is_empty() {
-                                            line_content.push_str(remaining);
-                                        }
-                                    }
-                                } else {
-                                    line_content.push_str(&text.value);
-                                }
-                            }
-                            Node::Link(link) => {
-                                let text = render_inline_content(&link.values);
-                                let url = link.url.as_str();
-                                if text.trim().is_empty() {
-                                    line_content.push_str(&format!(
-                                        " 🔗 {}",
-                                        make_clickable_link(url, url)
-                                    ));
-                                } else {
-                                    line_content.push_str(&format!(
-                                        " 🔗 {}",
-                                        make_clickable_link(url, &text)
-                                    ));
-                                }
-                            }
-                            _ => {
-                                // Handle all other inline formatting
-                                line_content.push_str(&render_inline_content(&[child.clone()]));
-                            }
-                        }
-                    }
-                    if !line_content.trim().is_empty() && found_callout_marker {
-                        writeln!(writer, "│ {}", line_content)?;
-                    }
-                }
-                _ => {
-                    if found_callout_marker {
-                        write!(writer, "│ ")?;
-                        render_node_inline(value, 0, false, highlighter, writer)?;
-                    }
-                }
+    }
+    writeln!(writer)?;
+    Ok(())
+}
+
+/// Render front matter according to [`RenderOptions::front_matter`]
+fn render_front_matter<W: Write>(
+    source: &str,
+    format: FrontMatterFormat,
+    ctx: &RenderContext,
+    writer: &mut W,
+) -> io::Result<()> {
+    match ctx.options.front_matter {
+        FrontMatterMode::Hide => {}
+        FrontMatterMode::Raw => {
+            for line in front_matter::raw_lines(source, format) {
+                writeln!(writer, "{}", line)?;
             }
+            writeln!(writer)?;
         }
+        FrontMatterMode::Panel => {
+            let front_matter = FrontMatter::parse(source, format);
+            if !front_matter.is_empty() {
+                for line in front_matter::panel_lines(&front_matter) {
+                    writeln!(writer, "{}", line)?;
+                }
+            }
 
-        writeln!(writer, "└─")?;
+            // A front matter title stands in for a missing h1
+            if !ctx.has_h1
+                && let Some(title) = front_matter.title()
+            {
+                render_heading(1, title, false, ctx, writer)?;
+            }
+        }
     }
     Ok(())
 }
 
-fn render_regular_blockquote<W: Write>(
-    blockquote: &mq_markdown::Blockquote,
+/// Render a sequence of inline nodes, attaching Pandoc `{.lang}` attributes to inline code
+fn render_inline_nodes<W: Write>(
+    nodes: &[Node],
     depth: usize,
-    highlighter: &mut SyntaxHighlighter,
+    ctx: &mut RenderContext,
     writer: &mut W,
 ) -> io::Result<()> {
-    for value in &blockquote.values {
-        write!(writer, "{} ", "▌".bright_black())?;
-        render_node_inline(value, depth, false, highlighter, writer)?;
+    let mut i = 0;
+    while i < nodes.len() {
+        let node = &nodes[i];
+        if let Some((text, end)) = inline_html_element(nodes, i, ctx) {
+            write!(writer, "{}", text)?;
+            i = end + 1;
+            continue;
+        }
+
+        match node {
+            Node::Html(html)
+                if html::parse_tag(&html.value).is_some_and(|tag| tag.name == "br") =>
+            {
+                writeln!(writer)?;
+            }
+            Node::CodeInline(code) => {
+                let (value, lang) = split_inline_code_lang(code.value.as_str());
+                let lang = lang.or_else(|| match nodes.get(i + 1) {
+                    Some(Node::Text(next)) => pandoc_lang_attribute(&next.value).map(|(l, _)| l),
+                    _ => None,
+                });
+                write!(writer, "{}", render_code_inline(value, lang, ctx))?;
+            }
+            Node::Text(text) if i > 0 && matches!(nodes[i - 1], Node::CodeInline(_)) => {
+                match pandoc_lang_attribute(&text.value) {
+                    Some((_, rest)) => write!(writer, "{}", rest)?,
+                    None => render_node_inline(node, depth, true, ctx, writer)?,
+                }
+            }
+            _ => render_node_inline(node, depth, true, ctx, writer)?,
+        }
+        i += 1;
     }
     Ok(())
 }
 
-fn render_inline_content(nodes: &[Node]) -> String {
-    let mut result = String::new();
-    for (i, node) in nodes.iter().enumerate() {
-        // Add space between inline elements if needed
-        if i > 0 && needs_space_before(node) && !result.ends_with(' ') {
-            result.push(' ');
-        }
+/// Render an inline HTML element split across sibling nodes, such as `<kbd>`, `Ctrl`, `</kbd>`.
+///
+/// Returns the styled text and the index of the closing tag.
+fn inline_html_element(
+    nodes: &[Node],
+    start: usize,
+    ctx: &RenderContext,
+) -> Option<(String, usize)> {
+    let Node::Html(html) = &nodes[start] else {
+        return None;
+    };
+    let tag = html::parse_tag(&html.value).filter(|tag| !tag.closing && !tag.is_void())?;
+    let end = html::find_closing(nodes, start, &tag.name)?;
+    let inner = render_inline_content(&nodes[start + 1..end], ctx);
+    let text = match tag.name.as_str() {
+        "a" => link_text(&inner, tag.attribute("href")?),
+        _ => html::style_inline(&tag, &inner)?,
+    };
+    Some((text, end))
+}
 
-        match node {
-            Node::Text(text) => result.push_str(&text.value),
-            Node::CodeInline(code) => result.push_str(&format!("`{}`", code.value)),
-            Node::Strong(strong) => result.push_str(&render_inline_content(&strong.values)),
-            Node::Emphasis(emphasis) => result.push_str(&render_inline_content(&emphasis.values)),
-            Node::Link(link) => {
-                let text = render_inline_content(&link.values);
-                let url = link.url.as_str();
-                if text.trim().is_empty() {
-                    result.push_str(&format!("🔗 {}", make_clickable_link(url, url)));
+/// Render interpreted HTML, centering lines from `align="center"` blocks in the layout width
+fn render_html_blocks<W: Write>(
+    blocks: &[HtmlBlock],
+    ctx: &mut RenderContext,
+    writer: &mut W,
+) -> io::Result<()> {
+    for block in blocks {
+        match block {
+            HtmlBlock::Line { text, centered } => {
+                let indent = if *centered {
+                    ctx.content_width()
+                        .saturating_sub(width::display_width(text))
+                        / 2
                 } else {
-                    result.push_str(&format!("🔗 {}", make_clickable_link(url, &text)));
-                }
+                    0
+                };
+                writeln!(writer, "{}{}", " ".repeat(indent), text)?;
+            }
+            HtmlBlock::Image { src, alt } => render_image(alt, src, writer)?,
+            HtmlBlock::Heading { depth, text } => {
+                render_heading(*depth, text, false, ctx, writer)?;
+            }
+            HtmlBlock::Rule => {
+                writeln!(writer, "{}", "─".repeat(ctx.content_width()).bright_black())?;
             }
-            _ => {}
         }
     }
-    result
+    writeln!(writer)?;
+    Ok(())
 }
 
-fn needs_space_before(node: &Node) -> bool {
-    matches!(
-        node,
-        Node::Link(_) | Node::Strong(_) | Node::Emphasis(_) | Node::CodeInline(_)
-    )
+/// Split a rehype-pretty style `{:lang}` suffix off inline code
+fn split_inline_code_lang(value: &str) -> (&str, Option<&str>) {
+    if let Some(inner) = value.strip_suffix('}')
+        && let Some(start) = inner.rfind("{:")
+    {
+        let lang = &inner[start + 2..];
+        if !lang.is_empty()
+            && lang
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '#' | '_'))
+        {
+            return (&value[..start], Some(lang));
+        }
+    }
+    (value, None)
 }
 
-fn get_node_children(node: &Node) -> Option<&Vec<Node>> {
-    match node {
-        Node::Fragment(fragment) => Some(&fragment.values),
-        Node::TableRow(row) => Some(&row.values),
-        Node::TableCell(cell) => Some(&cell.values),
-        _ => None,
+/// Parse a Pandoc `{.lang}` attribute at the start of the text following inline code.
+///
+/// Returns the language and the remaining text after the attribute.
+fn pandoc_lang_attribute(text: &str) -> Option<(&str, &str)> {
+    let inner = text.strip_prefix('{')?;
+    let end = inner.find('}')?;
+    let lang = inner[..end]
+        .split_whitespace()
+        .find_map(|attr| attr.strip_prefix('.'))
+        .filter(|lang| !lang.is_empty())?;
+    Some((lang, &inner[end + 1..]))
+}
+
+fn render_code_inline(value: &str, lang: Option<&str>, ctx: &mut RenderContext) -> String {
+    match lang {
+        Some(lang) if SyntaxHighlighter::is_supported(lang) => format!(
+            "{}{}{}",
+            "`".bright_black(),
+            ctx.highlighter.highlight(value, Some(lang)),
+            "`".bright_black()
+        ),
+        _ => format!("`{}`", value).bright_yellow().to_string(),
     }
 }
 
-/// Render a complete table with proper column alignment
-fn render_table<W: Write>(
-    table_nodes: &[&Node],
-    highlighter: &mut SyntaxHighlighter,
+fn render_code_block<W: Write>(
+    code: &mq_markdown::Code,
+    ctx: &mut RenderContext,
     writer: &mut W,
 ) -> io::Result<()> {
-    if table_nodes.is_empty() {
-        return Ok(());
+    write!(writer, "{}", "```".bright_black())?;
+    if let Some(lang) = &code.lang {
+        write!(writer, "{}", lang.bright_black())?;
     }
+    writeln!(writer)?;
 
-    // Calculate column widths from all cells
-    let all_nodes: Vec<Node> = table_nodes.iter().map(|n| (*n).clone()).collect();
-    let column_widths = calculate_column_widths(&all_nodes);
-
-    // Find table header to determine column count
-    let col_count = table_nodes
-        .iter()
-        .find_map(|node| {
-            if let Node::TableHeader(header) = node {
-                Some(header.align.len())
-            } else {
-                None
-            }
-        })
-        .unwrap_or(column_widths.len());
+    // Apply syntax highlighting if language is specified
+    let highlighted = match code.lang.as_deref() {
+        Some(lang) if diff::is_diff_language(lang) => {
+            diff::render_diff(&code.value, &mut ctx.highlighter)
+        }
+        lang if ctx.options.mark_syntax_errors => {
+            ctx.highlighter.highlight_with_errors(&code.value, lang)
+        }
+        lang => ctx.highlighter.highlight(&code.value, lang),
+    };
+    write!(writer, "{}", highlighted)?;
 
     writeln!(writer)?;
+    writeln!(writer, "{}", "```".bright_black())?;
+    writeln!(writer)?;
+    Ok(())
+}
 
-    // Render top border
-    render_table_top_border(&column_widths, col_count, writer)?;
+/// Render `$$display$$` math as a multi-line block centered in the layout width
+fn render_display_math<W: Write>(
+    source: &str,
+    ctx: &RenderContext,
+    writer: &mut W,
+) -> io::Result<()> {
+    let lines = math::render_display(source);
+    let block_width = lines
+        .iter()
+        .map(|line| width::display_width(line))
+        .max()
+        .unwrap_or(0);
+    let indent = " ".repeat(ctx.content_width().saturating_sub(block_width) / 2);
 
-    // Render cells row by row
-    write!(writer, "{}", "│ ".bright_cyan())?;
+    writeln!(writer)?;
+    for line in lines {
+        writeln!(writer, "{}{}", indent, line.italic())?;
+    }
+    writeln!(writer)?;
+    Ok(())
+}
 
-    for (i, node) in table_nodes.iter().enumerate() {
-        match node {
-            Node::TableCell(cell) => {
-                let content = render_inline_content(&cell.values);
-                let width = column_widths.get(cell.column).copied().unwrap_or(0);
+fn is_markdown_language(lang: &str) -> bool {
+    matches!(lang.to_lowercase().as_str(), "markdown" | "md")
+}
 
-                for value in &cell.values {
-                    render_node_inline(value, 0, true, highlighter, writer)?;
-                }
+/// Render a nested Markdown document inside a framed, indented panel
+fn render_markdown_preview<W: Write>(
+    markdown: &Markdown,
+    source: &str,
+    ctx: &mut RenderContext,
+    writer: &mut W,
+) -> io::Result<()> {
+    writeln!(writer, "  {}", "┌─ preview".bright_black())?;
+    let frame = format!("  {} ", "│".bright_black());
+    render_prefixed(&frame, &frame, ctx, writer, |ctx, buffer| {
+        // The nested document keeps its own footnotes and definitions, laid out in the frame
+        let mut nested = RenderContext::new(markdown, Some(source), ctx.options);
+        nested.prefixes = ctx.prefixes.clone();
+        render_document_nodes(markdown, &mut nested, buffer)
+    })?;
+    writeln!(writer, "  {}", "└─".bright_black())?;
+    writeln!(writer)?;
+    Ok(())
+}
 
-                // Pad with spaces to align columns
-                let content_width = content.chars().count();
-                if content_width < width {
-                    write!(writer, "{}", " ".repeat(width - content_width))?;
-                }
+/// Render all footnote definitions as a numbered section at the end of the document
+fn render_footnotes_section<W: Write>(ctx: &mut RenderContext, writer: &mut W) -> io::Result<()> {
+    let definitions = ctx.footnotes.definitions().to_vec();
+    if definitions.is_empty() {
+        return Ok(());
+    }
 
-                write!(writer, " {}", "│ ".bright_cyan())?;
-
-                if cell.last_cell_in_row {
-                    writeln!(writer)?;
-                    // Check if next node is the header separator or another cell
-                    if i + 1 < table_nodes.len() {
-                        if let Some(Node::TableHeader(header)) = table_nodes.get(i + 1) {
-                            render_table_header(header, &column_widths, writer)?;
-                            // After header, if there's another cell, start a new row
-                            if i + 2 < table_nodes.len()
-                                && matches!(table_nodes.get(i + 2), Some(Node::TableCell(_)))
-                            {
-                                write!(writer, "{}", "│ ".bright_cyan())?;
-                            }
-                        } else if matches!(table_nodes.get(i + 1), Some(Node::TableCell(_))) {
-                            // Start new row
-                            write!(writer, "{}", "│ ".bright_cyan())?;
-                        }
-                    }
-                }
+    writeln!(writer)?;
+    writeln!(writer, "{}", "─".repeat(20).bright_black())?;
+    writeln!(writer, "{}", "Footnotes".bold())?;
+
+    for (number, footnote) in definitions {
+        let label = format!("{}.", number);
+        write!(
+            writer,
+            "{} ",
+            make_clickable_link(&footnotes::definition_anchor(number), &label).bright_cyan()
+        )?;
+
+        let mut values = footnote.values.iter();
+        match values.next() {
+            Some(Node::Fragment(fragment)) => {
+                render_inline_nodes(&fragment.values, 0, ctx, writer)?;
+                writeln!(writer)?;
             }
-            Node::TableHeader(_) => {
-                // Already handled in the TableCell last_cell_in_row logic
+            Some(first) => {
+                writeln!(writer)?;
+                render_node(first, 1, ctx, writer)?;
             }
-            Node::TableRow(row) => {
-                render_table_row(row, &column_widths, highlighter, writer)?;
+            None => writeln!(writer)?,
+        }
+        for value in values {
+            render_node(value, 1, ctx, writer)?;
+        }
+    }
+    Ok(())
+}
+
+/// The items of the list starting at `nodes[0]`. Each item is its own `List` node, indexed
+/// from 0 within its list, so a new list starts where the index does not continue.
+fn list_items(nodes: &[Node]) -> Vec<&mq_markdown::List> {
+    let mut items: Vec<&mq_markdown::List> = Vec::new();
+    for node in nodes {
+        match (node, items.last()) {
+            (Node::List(list), None) => items.push(list),
+            (Node::List(list), Some(previous))
+                if list.index == previous.index + 1 && list.ordered == previous.ordered =>
+            {
+                items.push(list)
             }
-            _ => {}
+            _ => break,
         }
     }
+    items
+}
 
-    // Render bottom border
-    render_table_bottom_border(&column_widths, col_count, writer)?;
+/// Whether a list is loose: its items, or two blocks directly inside an item, are separated
+/// by a blank line in the source
+fn is_loose(items: &[&mq_markdown::List]) -> bool {
+    let separated = |a: Option<(usize, usize)>, b: Option<(usize, usize)>| matches!((a, b), (Some((_, end)), Some((start, _))) if start > end + 1);
+    let item_lines = |item: &mq_markdown::List| {
+        item.position
+            .as_ref()
+            .map(|position| (position.start.line, position.end.line))
+    };
 
-    writeln!(writer)?;
-    Ok(())
+    items
+        .windows(2)
+        .any(|pair| separated(item_lines(pair[0]), item_lines(pair[1])))
+        || items.iter().any(|item| {
+            item.values.windows(2).any(|pair| {
+                // Items of a nested list are spaced by that list, not this one
+                !matches!(pair, [Node::List(_), Node::List(_)])
+                    && separated(line_span(&pair[0]), line_span(&pair[1]))
+            })
+        })
 }
 
-/// Calculate column widths for a table
-fn calculate_column_widths(nodes: &[Node]) -> Vec<usize> {
-    let mut column_widths: Vec<usize> = Vec::new();
+/// First and last source line of a block node, if known
+fn line_span(node: &Node) -> Option<(usize, usize)> {
+    match node {
+        Node::Fragment(fragment) => Some((
+            fragment.values.first()?.position()?.start.line,
+            fragment.values.last()?.position()?.end.line,
+        )),
+        _ => node
+            .position()
+            .map(|position| (position.start.line, position.end.line)),
+    }
+}
 
-    for node in nodes {
-        match node {
-            Node::TableRow(row) => {
-                for (col_idx, cell_node) in row.values.iter().enumerate() {
-                    if let Node::TableCell(cell) = cell_node {
-                        let content = render_inline_content(&cell.values);
-                        let width = content.chars().count();
-
-                        if col_idx >= column_widths.len() {
-                            column_widths.resize(col_idx + 1, 0);
-                        }
-                        column_widths[col_idx] = column_widths[col_idx].max(width);
-                    }
-                }
-            }
-            Node::TableCell(cell) => {
-                let content = render_inline_content(&cell.values);
-                let width = content.chars().count();
+fn render_list<W: Write>(
+    items: &[&mq_markdown::List],
+    depth: usize,
+    ctx: &mut RenderContext,
+    writer: &mut W,
+) -> io::Result<()> {
+    let Some(first) = items.first() else {
+        return Ok(());
+    };
 
-                if cell.column >= column_widths.len() {
-                    column_widths.resize(cell.column + 1, 0);
+    // Ordered lists count from the first item's number, with the numbers right-aligned
+    let labels: Vec<String> = if first.ordered {
+        let start = ctx
+            .source
+            .zip(first.position.as_ref())
+            .and_then(|(source, position)| {
+                let line = source.lines().nth(position.start.line.checked_sub(1)?)?;
+                numbering::start_number(line, position.start.column)
+            })
+            .unwrap_or(1);
+        let ordered_depth = ctx.list_path.iter().filter(|label| label.is_some()).count();
+        let parent = match ctx.options.list_numbering {
+            ListNumbering::Hierarchical => ctx.list_path.last().cloned().flatten(),
+            _ => None,
+        };
+        items
+            .iter()
+            .map(|item| {
+                let number = numbering::item_number(
+                    start + (item.index - first.index) as u64,
+                    ordered_depth,
+                    ctx.options.list_numbering,
+                );
+                match &parent {
+                    Some(parent) => format!("{}{}.", parent, number),
+                    None => format!("{}.", number),
                 }
-                column_widths[cell.column] = column_widths[cell.column].max(width);
+            })
+            .collect()
+    } else {
+        vec![LIST_BULLETS[depth % LIST_BULLETS.len()].to_string(); items.len()]
+    };
+    let label_width = labels
+        .iter()
+        .map(|label| width::display_width(label))
+        .max()
+        .unwrap_or(0);
+
+    let loose = is_loose(items);
+    for (index, (item, label)) in items.iter().zip(&labels).enumerate() {
+        if loose && index > 0 {
+            writeln!(writer)?;
+        }
+
+        // Handle checkbox lists
+        let checkbox = match item.checked {
+            Some(true) => "☑️ ",
+            Some(false) => "☐ ",
+            None => "",
+        };
+
+        // Continuation lines hang under the item text
+        let marker = format!(
+            "{} {}",
+            format!("{:>width$}", label, width = label_width).bright_magenta(),
+            checkbox
+        );
+        let hanging = " ".repeat(label_width + 1);
+        ctx.list_path.push(item.ordered.then(|| label.clone()));
+        let result = render_prefixed(&marker, &hanging, ctx, writer, |ctx, buffer| {
+            if loose {
+                render_nodes_separated(&item.values, depth + 1, ctx, buffer)
+            } else {
+                render_nodes(&item.values, depth + 1, ctx, buffer)
             }
-            _ => {}
+        });
+        ctx.list_path.pop();
+        result?;
+    }
+
+    // Progress of the tasks in this list and its nested lists
+    if ctx.options.task_progress && ctx.list_path.is_empty() {
+        let progress = Progress::count(items.iter().copied());
+        if progress.total > 0 {
+            writeln!(writer, "{}", progress.bar())?;
         }
     }
+    Ok(())
+}
 
-    column_widths
+/// Render the progress of every task in the document
+fn render_task_summary<W: Write>(markdown: &Markdown, writer: &mut W) -> io::Result<()> {
+    let tasks = tasks::task_items(markdown);
+    if tasks.is_empty() {
+        return Ok(());
+    }
+    let progress = Progress {
+        done: tasks.iter().filter(|task| task.checked).count(),
+        total: tasks.len(),
+    };
+    writeln!(writer)?;
+    writeln!(writer, "{} {}", "Tasks".bold(), progress.bar())?;
+    Ok(())
 }
 
-/// Render table top border
-fn render_table_top_border<W: Write>(
-    column_widths: &[usize],
-    col_count: usize,
+fn render_callout_blockquote<W: Write>(
+    blockquote: &mq_markdown::Blockquote,
+    marker: &CalloutMarker,
+    depth: usize,
+    ctx: &mut RenderContext,
     writer: &mut W,
 ) -> io::Result<()> {
-    write!(writer, "{}", "┌".bright_black())?;
-    for i in 0..col_count {
-        let width = column_widths.get(i).copied().unwrap_or(4);
-        write!(writer, "{}", "─".repeat(width + 2).bright_black())?;
-        if i < col_count - 1 {
-            write!(writer, "{}", "┬".bright_black())?;
-        }
-    }
-    writeln!(writer, "{}", "┐".bright_black())?;
-    Ok(())
+    // The content without the marker line
+    let mut values = blockquote.values.clone();
+    let first_text = match values.first_mut() {
+        Some(Node::Fragment(para)) => match para.values.first_mut() {
+            Some(Node::Text(text)) => Some(text),
+            _ => None,
+        },
+        Some(Node::Text(text)) => Some(text),
+        _ => None,
+    };
+    if let Some(text) = first_text {
+        text.value = marker.body.to_string();
+    }
+
+    render_callout(
+        marker.style,
+        marker.title,
+        marker.fold,
+        &values,
+        depth,
+        ctx,
+        writer,
+    )
 }
 
-/// Render table bottom border
-fn render_table_bottom_border<W: Write>(
-    column_widths: &[usize],
-    col_count: usize,
+/// Render a callout box whose content is rendered like the rest of the document
+fn render_callout<W: Write>(
+    style: &CalloutStyle,
+    title: Option<&str>,
+    fold: Option<Fold>,
+    body: &[Node],
+    depth: usize,
+    ctx: &mut RenderContext,
     writer: &mut W,
 ) -> io::Result<()> {
-    write!(writer, "{}", "└".bright_black())?;
-    for i in 0..col_count {
-        let width = column_widths.get(i).copied().unwrap_or(4);
-        write!(writer, "{}", "─".repeat(width + 2).bright_black())?;
-        if i < col_count - 1 {
-            write!(writer, "{}", "┴".bright_black())?;
-        }
+    let expanded = match fold {
+        Some(Fold::Collapsed) => ctx.options.expand_details,
+        _ => true,
+    };
+    let fold_icon = match fold {
+        Some(_) if expanded => "▼ ",
+        Some(_) => "▶ ",
+        None => "",
+    };
+
+    // The callout header, with the custom title after the type's label
+    let header = match title {
+        Some(title) => format!("{} {}: {}", style.icon, style.label, title),
+        None => format!("{} {}", style.icon, style.label),
     }
-    writeln!(writer, "{}", "┘".bright_black())?;
-    Ok(())
+    .color(style.color)
+    .bold();
+    let header = format!("{}{}", fold_icon, header);
+    let body = if expanded { body } else { &[] };
+    render_boxed(&header, body, depth, ctx, writer)
 }
 
-/// Render table header with alignment and column widths
-fn render_table_header<W: Write>(
-    header: &mq_markdown::TableHeader,
-    column_widths: &[usize],
+fn render_regular_blockquote<W: Write>(
+    blockquote: &mq_markdown::Blockquote,
+    depth: usize,
+    ctx: &mut RenderContext,
     writer: &mut W,
 ) -> io::Result<()> {
-    write!(writer, "{}", "├".bright_black())?;
-    for (i, align) in header.align.iter().enumerate() {
-        let width = column_widths.get(i).copied().unwrap_or(4);
-        let (left, right) = match align {
-            mq_markdown::TableAlignKind::Left => (":", "─"),
-            mq_markdown::TableAlignKind::Right => ("─", ":"),
-            mq_markdown::TableAlignKind::Center => (":", ":"),
-            mq_markdown::TableAlignKind::None => ("─", "─"),
-        };
+    let bar = format!("{} ", "▌".bright_black());
+    render_prefixed(&bar, &bar, ctx, writer, |ctx, buffer| {
+        render_nodes(&blockquote.values, depth, ctx, buffer)
+    })
+}
 
-        write!(writer, "{}", left.bright_black())?;
-        write!(writer, "{}", "─".repeat(width).bright_black())?;
-        write!(writer, "{}", right.bright_black())?;
+fn render_inline_content(nodes: &[Node], ctx: &RenderContext) -> String {
+    let mut result = String::new();
+    let mut i = 0;
+    while i < nodes.len() {
+        let node = &nodes[i];
+        // Add space between inline elements if needed
+        if i > 0 && needs_space_before(node) && !result.ends_with(' ') {
+            result.push(' ');
+        }
 
-        if i < header.align.len() - 1 {
-            write!(writer, "{}", "┼".bright_black())?;
+        if let Some((text, end)) = inline_html_element(nodes, i, ctx) {
+            result.push_str(&text);
+            i = end + 1;
+            continue;
         }
+
+        let after_code = i > 0 && matches!(nodes[i - 1], Node::CodeInline(_));
+        match node {
+            Node::Text(text) if after_code => match pandoc_lang_attribute(&text.value) {
+                Some((_, rest)) => result.push_str(rest),
+                None => result.push_str(&style_text(&text.value, ctx)),
+            },
+            Node::Text(text) => result.push_str(&style_text(&text.value, ctx)),
+            Node::CodeInline(code) => {
+                let (value, _) = split_inline_code_lang(code.value.as_str());
+                result.push_str(&format!("`{}`", value))
+            }
+            Node::FootnoteRef(reference) => match ctx.footnotes.number(&reference.ident) {
+                Some(number) => result.push_str(&make_clickable_link(
+                    &footnotes::definition_anchor(number),
+                    &footnotes::superscript(number),
+                )),
+                None => result.push_str(&format!("[^{}]", reference.ident)),
+            },
+            Node::MathInline(math) => result.push_str(&math::render_inline(math.value.as_str())),
+            Node::Strong(strong) => result.push_str(&render_inline_content(&strong.values, ctx)),
+            Node::Delete(delete) => result.push_str(&render_delete(delete, ctx)),
+            Node::Emphasis(emphasis) => {
+                result.push_str(&render_inline_content(&emphasis.values, ctx))
+            }
+            Node::Link(link) => {
+                let text = render_inline_content(&link.values, ctx);
+                result.push_str(&link_text(&text, link.url.as_str()));
+            }
+            Node::LinkRef(link) => {
+                let text = render_inline_content(&link.values, ctx);
+                match ctx.definitions.url(&link.ident) {
+                    Some(url) => result.push_str(&link_text(&text, url)),
+                    None => result.push_str(&format!("[{}]", text)),
+                }
+            }
+            Node::Image(image) => result.push_str(&image_text(&image.alt, &image.url)),
+            Node::ImageRef(image) => match ctx.definitions.url(&image.ident) {
+                Some(url) => result.push_str(&image_text(&image.alt, url)),
+                None => result.push_str(&format!("![{}]", image.alt)),
+            },
+            Node::Html(html) => {
+                if let Some(text) = html::interpret_inline(&html.value) {
+                    result.push_str(&text);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
     }
-    writeln!(writer, "{}", "┤".bright_black())?;
-    Ok(())
+    result
 }
 
-/// Render table row with column widths
-fn render_table_row<W: Write>(
-    row: &mq_markdown::TableRow,
-    column_widths: &[usize],
-    highlighter: &mut SyntaxHighlighter,
+/// Text with the extended inline styles applied, if they are enabled
+fn style_text(text: &str, ctx: &RenderContext) -> String {
+    if ctx.options.inline_extensions {
+        inline_style::style_text(text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Render a GFM delete node, treating single-tilde spans as Pandoc subscript when the inline
+/// extensions are enabled
+fn render_delete(delete: &mq_markdown::Delete, ctx: &RenderContext) -> String {
+    let text = render_inline_content(&delete.values, ctx);
+    if ctx.options.inline_extensions && inline_style::is_subscript(delete) {
+        inline_style::subscript(&text)
+    } else {
+        inline_style::strikethrough(&text)
+    }
+}
+
+/// A link as inline text with a clickable OSC 8 hyperlink
+fn link_text(text: &str, url: &str) -> String {
+    if text.trim().is_empty() {
+        format!("🔗 {}", make_clickable_link(url, url))
+    } else {
+        format!("🔗 {}", make_clickable_link(url, text))
+    }
+}
+
+/// An image as inline text: its alt text, or its URL without one. It is not a hyperlink, as
+/// images often sit inside link text, such as badges.
+fn image_text(alt: &str, url: &str) -> String {
+    if alt.trim().is_empty() {
+        format!("🖼️ {}", url)
+    } else {
+        format!("🖼️ {}", alt)
+    }
+}
+
+fn needs_space_before(node: &Node) -> bool {
+    matches!(
+        node,
+        Node::Link(_)
+            | Node::LinkRef(_)
+            | Node::Strong(_)
+            | Node::Emphasis(_)
+            | Node::CodeInline(_)
+    )
+}
+
+fn get_node_children(node: &Node) -> Option<&Vec<Node>> {
+    match node {
+        Node::Fragment(fragment) => Some(&fragment.values),
+        Node::TableRow(row) => Some(&row.values),
+        Node::TableCell(cell) => Some(&cell.values),
+        _ => None,
+    }
+}
+
+/// Render a table, wrapping cells so it fits the layout width, or as one box per row when
+/// the columns cannot fit side by side
+fn render_table<W: Write>(
+    table: &Table,
+    ctx: &mut RenderContext,
     writer: &mut W,
 ) -> io::Result<()> {
-    write!(writer, "{}", "│ ".bright_cyan())?;
-    for (col_idx, cell_node) in row.values.iter().enumerate() {
-        if let Node::TableCell(cell) = cell_node {
-            let content = render_inline_content(&cell.values);
-            let width = column_widths.get(col_idx).copied().unwrap_or(0);
-
-            for value in &cell.values {
-                render_node_inline(value, 0, true, highlighter, writer)?;
-            }
+    let col_count = table.columns();
+    if col_count == 0 {
+        return Ok(());
+    }
+
+    let header = table
+        .header
+        .as_ref()
+        .map(|cells| render_table_row_text(cells, ctx))
+        .transpose()?;
+    let rows = table
+        .rows
+        .iter()
+        .map(|cells| render_table_row_text(cells, ctx))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let mut natural = vec![0; col_count];
+    let mut minimum = vec![0; col_count];
+    for row in header.iter().chain(&rows) {
+        for (i, cell) in row.iter().enumerate() {
+            natural[i] = natural[i].max(width::display_width(cell));
+            minimum[i] = minimum[i].max(table::min_width(cell));
+        }
+    }
 
-            // Pad with spaces to align columns
-            let content_width = content.chars().count();
-            if content_width < width {
-                write!(writer, "{}", " ".repeat(width - content_width))?;
+    writeln!(writer)?;
+    let available = ctx
+        .content_width()
+        .saturating_sub(table::border_width(col_count));
+    let Some(column_widths) = table::fit_columns(&natural, &minimum, available) else {
+        let (labels, records) = match header {
+            Some(header) if rows.is_empty() => (Vec::new(), vec![header]),
+            header => (header.unwrap_or_default(), rows),
+        };
+        return render_table_records(&labels, &records, ctx.content_width(), writer);
+    };
+
+    // Columns follow the header's alignment; unaligned columns of numbers can be right-aligned
+    let aligns: Vec<Align> = (0..col_count)
+        .map(|col| match table.alignment(col) {
+            mq_markdown::TableAlignKind::None
+                if ctx.options.align_numbers && is_numeric_column(&rows, col) =>
+            {
+                Align::Right
             }
+            kind => kind.into(),
+        })
+        .collect();
 
-            write!(writer, " {}", "│ ".bright_cyan())?;
-        }
+    render_table_border(&column_widths, ["┌", "┬", "┐"], writer)?;
+    if let Some(header) = &header {
+        render_table_cells(header, &column_widths, &aligns, true, writer)?;
+        render_table_separator(table, &column_widths, writer)?;
     }
+    for row in &rows {
+        render_table_cells(row, &column_widths, &aligns, false, writer)?;
+    }
+    render_table_border(&column_widths, ["└", "┴", "┘"], writer)?;
+
     writeln!(writer)?;
     Ok(())
 }
 
-/// Render table cell with column width
-fn render_table_cell<W: Write>(
-    cell: &mq_markdown::TableCell,
+/// Render one table row, wrapping each cell within its column
+fn render_table_cells<W: Write>(
+    row: &[String],
     column_widths: &[usize],
-    highlighter: &mut SyntaxHighlighter,
+    aligns: &[Align],
+    header: bool,
     writer: &mut W,
 ) -> io::Result<()> {
-    write!(writer, "{}", "│ ".bright_cyan())?;
+    let cells: Vec<Vec<String>> = column_widths
+        .iter()
+        .enumerate()
+        .map(|(col, width)| width::wrap(row.get(col).map_or("", String::as_str), *width))
+        .collect();
+    let height = cells.iter().map(Vec::len).max().unwrap_or(1);
+    for line in 0..height {
+        write!(writer, "{}", "│ ".bright_cyan())?;
+        for ((cell, width), align) in cells.iter().zip(column_widths).zip(aligns) {
+            let text = cell.get(line).map_or("", String::as_str);
+            let text = table::pad(text, *width, *align);
+            if header {
+                write!(writer, "{}", text.bold())?;
+            } else {
+                write!(writer, "{}", text)?;
+            }
+            write!(writer, " {}", "│ ".bright_cyan())?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
 
-    let content = render_inline_content(&cell.values);
-    let width = column_widths.get(cell.column).copied().unwrap_or(0);
+/// Whether every non-empty cell of a column reads as a number
+fn is_numeric_column(rows: &[Vec<String>], col: usize) -> bool {
+    let mut cells = rows
+        .iter()
+        .filter_map(|row| row.get(col))
+        .filter(|cell| !cell.trim().is_empty())
+        .peekable();
+    cells.peek().is_some() && cells.all(|cell| table::is_numeric(cell))
+}
 
-    for value in &cell.values {
-        render_node_inline(value, 0, true, highlighter, writer)?;
-    }
+/// Styled text of each cell of a table row, on a single line
+fn render_table_row_text(cells: &[&[Node]], ctx: &mut RenderContext) -> io::Result<Vec<String>> {
+    cells
+        .iter()
+        .map(|values| {
+            let mut buffer = Vec::new();
+            render_inline_nodes(values, 0, ctx, &mut buffer)?;
+            Ok(String::from_utf8_lossy(&buffer).replace('\n', " "))
+        })
+        .collect()
+}
 
-    // Pad with spaces to align columns
-    let content_width = content.chars().count();
-    if content_width < width {
-        write!(writer, "{}", " ".repeat(width - content_width))?;
+/// Render each table row as a box of `label  value` lines, labelled by the header cells
+fn render_table_records<W: Write>(
+    labels: &[String],
+    rows: &[Vec<String>],
+    width: usize,
+    writer: &mut W,
+) -> io::Result<()> {
+    let inner = width.saturating_sub(4).max(2);
+    let label_width = labels
+        .iter()
+        .map(|label| width::display_width(label))
+        .max()
+        .unwrap_or(0)
+        .min(inner / 3);
+    let value_width = inner.saturating_sub(label_width + 2).max(1);
+
+    for row in rows {
+        writeln!(
+            writer,
+            "{}",
+            format!("┌{}┐", "─".repeat(inner + 2)).bright_black()
+        )?;
+        for (col, value) in row.iter().enumerate() {
+            let label = labels.get(col).cloned().unwrap_or_default();
+            let label_lines = width::wrap(&label, label_width.max(1));
+            let value_lines = width::wrap(value, value_width);
+            for line in 0..label_lines.len().max(value_lines.len()) {
+                let label = label_lines.get(line).map_or("", String::as_str);
+                let value = value_lines.get(line).map_or("", String::as_str);
+                let used = label_width + 2 + width::display_width(value);
+                write!(writer, "{} ", "│".bright_cyan())?;
+                write!(writer, "{}", label.bold())?;
+                write!(
+                    writer,
+                    "{}{}",
+                    " ".repeat(label_width.saturating_sub(width::display_width(label)) + 2),
+                    value
+                )?;
+                writeln!(
+                    writer,
+                    "{} {}",
+                    " ".repeat(inner.saturating_sub(used)),
+                    "│".bright_cyan()
+                )?;
+            }
+        }
+        writeln!(
+            writer,
+            "{}",
+            format!("└{}┘", "─".repeat(inner + 2)).bright_black()
+        )?;
     }
 
-    write!(writer, " ")?;
-    if cell.last_cell_in_row {
-        writeln!(writer, "{}", "│".bright_cyan())?;
-    }
+    writeln!(writer)?;
     Ok(())
 }
 
+/// Render a horizontal table border with the given left, junction and right characters
+fn render_table_border<W: Write>(
+    column_widths: &[usize],
+    [left, junction, right]: [&str; 3],
+    writer: &mut W,
+) -> io::Result<()> {
+    let segments: Vec<String> = column_widths
+        .iter()
+        .map(|width| "─".repeat(width + 2))
+        .collect();
+    writeln!(
+        writer,
+        "{}",
+        format!("{}{}{}", left, segments.join(junction), right).bright_black()
+    )
+}
+
+/// Render the separator under the header, marking each column's alignment with `:`
+fn render_table_separator<W: Write>(
+    table: &Table,
+    column_widths: &[usize],
+    writer: &mut W,
+) -> io::Result<()> {
+    let segments: Vec<String> = column_widths
+        .iter()
+        .enumerate()
+        .map(|(col, width)| {
+            let (left, right) = match table.alignment(col) {
+                mq_markdown::TableAlignKind::Left => (":", "─"),
+                mq_markdown::TableAlignKind::Right => ("─", ":"),
+                mq_markdown::TableAlignKind::Center => (":", ":"),
+                mq_markdown::TableAlignKind::None => ("─", "─"),
+            };
+            format!("{}{}{}", left, "─".repeat(*width), right)
+        })
+        .collect();
+    writeln!(
+        writer,
+        "{}",
+        format!("├{}┤", segments.join("┼")).bright_black()
+    )
+}
+
 /// Render an image to the terminal if possible
 fn render_image_to_terminal(path: &str) -> io::Result<()> {
     // Check if the path is a local file
@@ -920,8 +1940,19 @@ fn render_image_to_terminal(path: &str) -> io::Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::figlet::BannerFont;
+    use crate::width::strip_ansi;
+    use rstest::rstest;
+
     use mq_markdown::{Markdown, Node};
 
+    fn render_inline(nodes: &[Node]) -> String {
+        let markdown: Markdown = "".parse().unwrap();
+        let options = RenderOptions::default();
+        let ctx = RenderContext::new(&markdown, None, &options);
+        render_inline_content(nodes, &ctx)
+    }
+
     #[test]
     fn test_render_markdown_to_string_simple_text() {
         let markdown: Markdown = "Hello World".parse().unwrap();
@@ -941,6 +1972,40 @@ mod tests {
         assert!(result.contains("Heading 6"));
     }
 
+    #[test]
+    fn test_render_markdown_large_headings() {
+        let markdown: Markdown = "# Title\n\n## Section\n\n### Sub".parse().unwrap();
+        let options = RenderOptions {
+            heading_style: HeadingStyle::DoubleHeight,
+            ..Default::default()
+        };
+        let result = render_markdown_to_string_with_options(&markdown, &options).unwrap();
+        assert!(result.contains("\x1b#3① Title"));
+        assert!(result.contains("\x1b#4① Title"));
+        assert!(result.contains("\x1b#6② Section"));
+        assert!(!result.contains("═"));
+        // h3 and below keep the regular style
+        assert!(result.contains("③"));
+        assert!(!result.contains("\x1b#6③"));
+    }
+
+    #[test]
+    fn test_render_markdown_banner_headings() {
+        let markdown: Markdown = "# Hi\n\n## There".parse().unwrap();
+        let options = RenderOptions {
+            heading_style: HeadingStyle::Banner {
+                font: BannerFont::HalfBlock,
+                include_h2: false,
+            },
+            ..Default::default()
+        };
+        let result =
+            strip_ansi(&render_markdown_to_string_with_options(&markdown, &options).unwrap());
+        assert!(result.contains("█"));
+        assert!(!result.contains("① Hi"));
+        assert!(result.contains("② There"));
+    }
+
     #[test]
     fn test_render_markdown_to_string_list() {
         let markdown: Markdown = "- Item 1\n- Item 2\n- Item 3".parse().unwrap();
@@ -1003,39 +2068,179 @@ mod tests {
 
     #[test]
     fn test_detect_callout_note() {
-        assert!(detect_callout("[!NOTE] Test").is_some());
+        assert!(Callouts::default().detect("[!NOTE] Test").is_some());
     }
 
     #[test]
     fn test_detect_callout_tip() {
-        assert!(detect_callout("[!TIP] Test").is_some());
+        assert!(Callouts::default().detect("[!TIP] Test").is_some());
     }
 
     #[test]
     fn test_detect_callout_important() {
-        assert!(detect_callout("[!IMPORTANT] Test").is_some());
+        assert!(Callouts::default().detect("[!IMPORTANT] Test").is_some());
     }
 
     #[test]
     fn test_detect_callout_warning() {
-        assert!(detect_callout("[!WARNING] Test").is_some());
+        assert!(Callouts::default().detect("[!WARNING] Test").is_some());
     }
 
     #[test]
     fn test_detect_callout_caution() {
-        assert!(detect_callout("[!CAUTION] Test").is_some());
+        assert!(Callouts::default().detect("[!CAUTION] Test").is_some());
     }
 
     #[test]
     fn test_detect_callout_case_insensitive() {
-        assert!(detect_callout("[!note] Test").is_some());
-        assert!(detect_callout("[!Note] Test").is_some());
+        assert!(Callouts::default().detect("[!note] Test").is_some());
+        assert!(Callouts::default().detect("[!Note] Test").is_some());
     }
 
     #[test]
     fn test_detect_callout_none() {
-        assert!(detect_callout("Regular text").is_none());
-        assert!(detect_callout("[NOTE] No exclamation").is_none());
+        assert!(Callouts::default().detect("Regular text").is_none());
+        assert!(
+            Callouts::default()
+                .detect("[NOTE] No exclamation")
+                .is_none()
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "> [!warning] Breaking change\n> The API moved.",
+        "Breaking change",
+        true
+    )]
+    #[case("> [!bug]\n> Crashes on start.", "Bug", true)]
+    #[case("> [!note]- Folded\n> Hidden body", "▶", false)]
+    #[case("> [!note]+ Unfolded\n> Shown body", "▼", true)]
+    fn test_render_markdown_callout_titles_and_folding(
+        #[case] source: &str,
+        #[case] header: &str,
+        #[case] shows_body: bool,
+    ) {
+        let markdown: Markdown = source.parse().unwrap();
+        let result = render_markdown_to_string(&markdown).unwrap();
+        let first_line = result.trim_start().lines().next().unwrap();
+        assert!(first_line.starts_with("┌─"));
+        assert!(first_line.contains(header));
+        assert_eq!(result.contains("body") || result.contains("."), shows_body);
+        assert!(!result.contains("[!"));
+    }
+
+    #[test]
+    fn test_render_markdown_callout_block_content() {
+        let markdown: Markdown =
+            "> [!WARNING]\n> Run this first:\n>\n> ```sh\n> cargo build\n> cargo test\n> ```\n>\n> - one\n> - two"
+                .parse()
+                .unwrap();
+        let options = RenderOptions {
+            callout_box: CalloutBox::Full,
+            width: 40,
+            ..Default::default()
+        };
+        let result = render_markdown_to_string_with_options(&markdown, &options).unwrap();
+        let lines: Vec<&str> = result.trim().lines().collect();
+        assert!(lines[0].starts_with("┌─"));
+        assert!(lines[0].ends_with("┐"));
+        assert!(lines.last().unwrap().starts_with("└"));
+        for line in &lines[1..lines.len() - 1] {
+            assert!(line.starts_with("│"), "missing left border: {:?}", line);
+            assert!(line.ends_with("│"), "missing right border: {:?}", line);
+        }
+        assert!(result.contains("cargo build"));
+        assert!(result.contains("cargo test"));
+        assert!(result.contains("two"));
+    }
+
+    #[rstest]
+    #[case(CalloutBox::Full, 40)]
+    #[case(CalloutBox::Fit, 40)]
+    fn test_render_markdown_closed_callout_fits_width(
+        #[case] callout_box: CalloutBox,
+        #[case] width: usize,
+    ) {
+        let markdown: Markdown =
+            "> [!NOTE]\n> Above\n>\n> ---\n>\n> | Name | Value |\n> | --- | --- |\n> | a | 1 |"
+                .parse()
+                .unwrap();
+        let options = RenderOptions {
+            callout_box,
+            width,
+            ..Default::default()
+        };
+        let result = render_markdown_to_string_with_options(&markdown, &options).unwrap();
+        let lines: Vec<&str> = result.trim().lines().collect();
+        assert!(lines.iter().any(|line| line.contains("──────────")));
+        assert!(lines.iter().any(|line| line.contains("Value")));
+        for line in &lines {
+            assert_eq!(width::display_width(line), width, "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn test_render_markdown_mkdocs_admonition() {
+        let markdown: Markdown = "!!! warning \"Breaking change\"\n\n    The API moved.\n\n    ```sh\n    cargo update\n    ```\n\nAfter"
+            .parse()
+            .unwrap();
+        let result = render_markdown_to_string(&markdown).unwrap();
+        assert!(result.contains("⚠️ Warning: Breaking change"));
+        assert!(result.contains("│ The API moved."));
+        let code_line = result.lines().find(|line| line.contains("cargo")).unwrap();
+        assert!(code_line.starts_with("│ "));
+        assert!(!result.contains("!!!"));
+        assert!(result.contains("After"));
+    }
+
+    #[rstest]
+    #[case(
+        ":::danger Careful\nDo not run this.\n\n- as root\n:::\n\nAfter",
+        "⚡ Danger: Careful",
+        "│ ● as root"
+    )]
+    #[case(
+        ":::tip[Pro tip]\n\nUse the cache.\n\n:::\n\nAfter",
+        "💡 Tip: Pro tip",
+        "│ Use the cache."
+    )]
+    fn test_render_markdown_fenced_admonition(
+        #[case] source: &str,
+        #[case] header: &str,
+        #[case] body: &str,
+    ) {
+        let markdown: Markdown = source.parse().unwrap();
+        let result = strip_ansi(&render_markdown_to_string(&markdown).unwrap());
+        assert!(result.contains(header));
+        assert!(result.contains(body));
+        assert!(!result.contains(":::"));
+        assert!(!result.contains("│ After"));
+    }
+
+    #[test]
+    fn test_render_markdown_unclosed_admonition_stays_text() {
+        let markdown: Markdown = ":::note\nNo closing fence".parse().unwrap();
+        let result = render_markdown_to_string(&markdown).unwrap();
+        assert!(result.contains(":::note"));
+        assert!(!result.contains("┌─"));
+    }
+
+    #[test]
+    fn test_render_markdown_custom_callout_type() {
+        let markdown: Markdown = "> [!adr]\n> Use SQLite.".parse().unwrap();
+        let options = RenderOptions {
+            callouts: Callouts::default()
+                .with_type(
+                    "DECISION",
+                    crate::CalloutStyle::new("⚖️", colored::Color::Cyan, "Decision"),
+                )
+                .with_alias("ADR", "DECISION"),
+            ..Default::default()
+        };
+        let result = render_markdown_to_string_with_options(&markdown, &options).unwrap();
+        assert!(result.contains("⚖️ Decision"));
+        assert!(result.contains("Use SQLite."));
     }
 
     #[test]
@@ -1051,7 +2256,7 @@ mod tests {
             value: "Hello".to_string(),
             position: None,
         })];
-        let result = render_inline_content(&nodes);
+        let result = render_inline(&nodes);
         assert_eq!(result, "Hello");
     }
 
@@ -1061,7 +2266,7 @@ mod tests {
             value: "code".into(),
             position: None,
         })];
-        let result = render_inline_content(&nodes);
+        let result = render_inline(&nodes);
         assert_eq!(result, "`code`");
     }
 
@@ -1074,21 +2279,72 @@ mod tests {
             })],
             position: None,
         })];
-        let result = render_inline_content(&nodes);
+        let result = render_inline(&nodes);
         assert_eq!(result, "bold");
     }
 
     #[test]
-    fn test_render_inline_content_emphasis() {
-        let nodes = vec![Node::Emphasis(mq_markdown::Emphasis {
-            values: vec![Node::Text(mq_markdown::Text {
-                value: "italic".to_string(),
-                position: None,
-            })],
-            position: None,
-        })];
-        let result = render_inline_content(&nodes);
-        assert_eq!(result, "italic");
+    fn test_render_inline_content_emphasis() {
+        let nodes = vec![Node::Emphasis(mq_markdown::Emphasis {
+            values: vec![Node::Text(mq_markdown::Text {
+                value: "italic".to_string(),
+                position: None,
+            })],
+            position: None,
+        })];
+        let result = render_inline(&nodes);
+        assert_eq!(result, "italic");
+    }
+
+    #[test]
+    fn test_split_inline_code_lang() {
+        assert_eq!(
+            split_inline_code_lang("let x = 1{:rust}"),
+            ("let x = 1", Some("rust"))
+        );
+        assert_eq!(split_inline_code_lang("plain"), ("plain", None));
+        assert_eq!(split_inline_code_lang("{:}"), ("{:}", None));
+        assert_eq!(
+            split_inline_code_lang("a{:not a lang}"),
+            ("a{:not a lang}", None)
+        );
+    }
+
+    #[test]
+    fn test_pandoc_lang_attribute() {
+        assert_eq!(
+            pandoc_lang_attribute("{.rust} rest"),
+            Some(("rust", " rest"))
+        );
+        assert_eq!(
+            pandoc_lang_attribute("{#id .python .numberLines}"),
+            Some(("python", ""))
+        );
+        assert_eq!(pandoc_lang_attribute("{#id}"), None);
+        assert_eq!(pandoc_lang_attribute("no attribute"), None);
+    }
+
+    #[test]
+    fn test_render_markdown_inline_code_lang_hints() {
+        let markdown: Markdown = "Use `let x = 1{:rust}` and `print(1)`{.python} here"
+            .parse()
+            .unwrap();
+        let result = render_markdown_to_string(&markdown).unwrap();
+        assert!(!result.contains("{:rust}"));
+        assert!(!result.contains("{.python}"));
+        assert!(result.contains("let x = 1"));
+        assert!(result.contains("print(1)"));
+        assert!(result.contains(" here"));
+    }
+
+    #[test]
+    fn test_render_inline_content_strips_lang_hints() {
+        let markdown: Markdown = "`x{:rust}` and `y`{.go} end".parse().unwrap();
+        let Some(Node::Fragment(fragment)) = markdown.nodes.first() else {
+            panic!("expected a paragraph, got {:?}", markdown.nodes);
+        };
+        let result = render_inline(&fragment.values);
+        assert_eq!(result, "`x` and `y` end");
     }
 
     #[test]
@@ -1111,37 +2367,6 @@ mod tests {
         }
     }
 
-    #[test]
-    fn test_calculate_column_widths() {
-        let nodes = vec![
-            Node::TableCell(mq_markdown::TableCell {
-                values: vec![Node::Text(mq_markdown::Text {
-                    value: "Short".to_string(),
-                    position: None,
-                })],
-                column: 0,
-                row: 0,
-                last_cell_in_row: false,
-                last_cell_of_in_table: false,
-                position: None,
-            }),
-            Node::TableCell(mq_markdown::TableCell {
-                values: vec![Node::Text(mq_markdown::Text {
-                    value: "Very Long Text".to_string(),
-                    position: None,
-                })],
-                column: 1,
-                row: 0,
-                last_cell_in_row: true,
-                last_cell_of_in_table: true,
-                position: None,
-            }),
-        ];
-        let widths = calculate_column_widths(&nodes);
-        assert_eq!(widths[0], 5); // "Short"
-        assert_eq!(widths[1], 14); // "Very Long Text"
-    }
-
     #[test]
     fn test_render_markdown_ordered_list() {
         let markdown: Markdown = "1. First\n2. Second\n3. Third".parse().unwrap();
@@ -1172,6 +2397,163 @@ mod tests {
         assert!(result.contains("Cell 2"));
     }
 
+    #[test]
+    fn test_render_markdown_table_wraps_to_width() {
+        let markdown: Markdown = "| Name | Description |\n|---|---|\n| mqv | A terminal markdown viewer with rich text rendering and syntax highlighting |"
+            .parse()
+            .unwrap();
+        let options = RenderOptions {
+            width: 40,
+            ..Default::default()
+        };
+        let result =
+            strip_ansi(&render_markdown_to_string_with_options(&markdown, &options).unwrap());
+        let lines: Vec<&str> = result.lines().filter(|line| !line.is_empty()).collect();
+        assert!(
+            lines
+                .iter()
+                .all(|line| line.trim_end().chars().count() <= 40)
+        );
+        assert!(lines.iter().filter(|line| line.starts_with("│ ")).count() > 2);
+        assert!(result.contains("highlighting"));
+    }
+
+    #[rstest]
+    #[case(false, "│ apple  │  1.50 │ 3   │", "│ cherry │ 12.00 │ 10  │")]
+    #[case(true, "│ apple  │  1.50 │   3 │", "│ cherry │ 12.00 │  10 │")]
+    fn test_render_markdown_table_alignment(
+        #[case] align_numbers: bool,
+        #[case] first: &str,
+        #[case] second: &str,
+    ) {
+        let markdown: Markdown =
+            "| Item | Price | Qty |\n|---|--:|---|\n| apple | 1.50 | 3 |\n| cherry | 12.00 | 10 |"
+                .parse()
+                .unwrap();
+        let options = RenderOptions {
+            align_numbers,
+            ..Default::default()
+        };
+        let result =
+            strip_ansi(&render_markdown_to_string_with_options(&markdown, &options).unwrap());
+        let lines: Vec<&str> = result.lines().map(str::trim_end).collect();
+        assert!(lines.contains(&first), "{}", result);
+        assert!(lines.contains(&second), "{}", result);
+    }
+
+    #[test]
+    fn test_render_markdown_table_center_alignment() {
+        let markdown: Markdown = "| Status |\n|:-:|\n| ok |".parse().unwrap();
+        let result = strip_ansi(&render_markdown_to_string(&markdown).unwrap());
+        assert!(
+            result.lines().any(|line| line.trim_end() == "│   ok   │"),
+            "{}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_render_markdown_wide_characters() {
+        let markdown: Markdown =
+            "## 見出し\n\n| 名前 | 絵文字 |\n|---|---|\n| テスト | 👍 |\n| [link](https://example.com) | ☑️ |"
+                .parse()
+                .unwrap();
+        let result = strip_ansi(&render_markdown_to_string(&markdown).unwrap());
+        assert!(
+            result.lines().any(|line| line == "─".repeat(10)),
+            "{}",
+            result
+        );
+
+        let table_widths: Vec<usize> = result
+            .lines()
+            .filter(|line| line.starts_with(['┌', '│', '├', '└']))
+            .map(|line| width::display_width(line.trim_end()))
+            .collect();
+        assert_eq!(table_widths.len(), 6);
+        assert!(table_widths.iter().all(|width| *width == table_widths[0]));
+    }
+
+    #[test]
+    fn test_render_markdown_table_edge_cases() {
+        // Header only
+        let markdown: Markdown = "| a | b |\n|---|---|".parse().unwrap();
+        let result = strip_ansi(&render_markdown_to_string(&markdown).unwrap());
+        assert!(result.contains("│ a │ b │"), "{}", result);
+        assert!(result.contains("├───┼───┤"), "{}", result);
+
+        // Ragged rows are padded to the widest row
+        let markdown: Markdown = "| a | b |\n|---|---|\n| 1 |\n| 2 | 3 | 4 |"
+            .parse()
+            .unwrap();
+        let result = strip_ansi(&render_markdown_to_string(&markdown).unwrap());
+        let widths: Vec<usize> = result
+            .lines()
+            .filter(|line| line.starts_with(['┌', '│', '├', '└']))
+            .map(|line| width::display_width(line.trim_end()))
+            .collect();
+        assert!(widths.iter().all(|width| *width == widths[0]), "{}", result);
+
+        // Adjacent tables stay separate
+        let markdown: Markdown = "| a |\n|---|\n| 1 |\n\n| b |\n|---|\n| 2 |"
+            .parse()
+            .unwrap();
+        let result = strip_ansi(&render_markdown_to_string(&markdown).unwrap());
+        assert_eq!(result.matches('┌').count(), 2, "{}", result);
+    }
+
+    #[test]
+    fn test_render_table_without_alignments() {
+        let cell = |text: &str, column: usize| {
+            Node::TableCell(mq_markdown::TableCell {
+                values: vec![Node::Text(mq_markdown::Text {
+                    value: text.to_string(),
+                    position: None,
+                })],
+                column,
+                row: 0,
+                last_cell_in_row: column == 1,
+                last_cell_of_in_table: false,
+                position: None,
+            })
+        };
+        let nodes = vec![
+            cell("a", 0),
+            cell("b", 1),
+            Node::TableHeader(mq_markdown::TableHeader {
+                align: Vec::new(),
+                position: None,
+            }),
+        ];
+        let markdown: Markdown = "".parse().unwrap();
+        let options = RenderOptions::default();
+        let mut ctx = RenderContext::new(&markdown, None, &options);
+        let mut output = Vec::new();
+        render_table(&Table::from_nodes(&nodes), &mut ctx, &mut output).unwrap();
+        let result = strip_ansi(&String::from_utf8(output).unwrap());
+        assert!(result.contains("├───┼───┤"), "{}", result);
+    }
+
+    #[test]
+    fn test_render_markdown_table_records_when_too_narrow() {
+        let markdown: Markdown = "| Name | Description |\n|---|---|\n| mqv | A terminal markdown viewer |\n| mq | jq-like |"
+            .parse()
+            .unwrap();
+        let options = RenderOptions {
+            width: 20,
+            ..Default::default()
+        };
+        let result =
+            strip_ansi(&render_markdown_to_string_with_options(&markdown, &options).unwrap());
+        assert_eq!(result.matches('┌').count(), 2);
+        assert!(!result.contains('┬'));
+        assert!(
+            result
+                .lines()
+                .any(|line| line.starts_with("│ Name") && line.contains("mq "))
+        );
+    }
+
     #[test]
     fn test_render_markdown_nested_list() {
         let markdown: Markdown = "- Item 1\n  - Nested 1\n  - Nested 2\n- Item 2"
@@ -1184,6 +2566,137 @@ mod tests {
         assert!(result.contains("Item 2"));
     }
 
+    #[test]
+    fn test_render_markdown_quote_list_code_prefixes() {
+        let markdown: Markdown =
+            "> Intro\n>\n> - item\n>\n>   ```rust\n>   let a = 1;\n>   let b = 2;\n>   ```\n>\n> > inner\n> > more"
+                .parse()
+                .unwrap();
+        let result = render_markdown_to_string(&markdown).unwrap();
+        let lines: Vec<&str> = result.trim().lines().collect();
+        for line in &lines {
+            assert!(line.contains("▌"), "missing quote bar: {:?}", line);
+        }
+        assert!(lines.iter().any(|line| line.contains("let b")));
+        assert_eq!(
+            lines
+                .iter()
+                .filter(|line| line.matches("▌").count() == 2)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_render_markdown_list_item_code_is_indented() {
+        let markdown: Markdown = "- item\n\n  ```sh\n  cargo build\n  cargo test\n  ```"
+            .parse()
+            .unwrap();
+        let result = render_markdown_to_string(&markdown).unwrap();
+        let code_lines: Vec<&str> = result
+            .lines()
+            .filter(|line| line.contains("cargo"))
+            .collect();
+        assert_eq!(code_lines.len(), 2);
+        for line in code_lines {
+            assert!(line.starts_with("  "), "not indented: {:?}", line);
+        }
+    }
+
+    #[test]
+    fn test_render_markdown_loose_list_keeps_blank_lines() {
+        let markdown: Markdown =
+            "1. Install\n\n   ```sh\n   cargo install mqv\n   ```\n\n2. Run it\n\n   Second paragraph.\n3. Done"
+                .parse()
+                .unwrap();
+        let result = strip_ansi(&render_markdown_to_string(&markdown).unwrap());
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines[0], "1. Install");
+        assert_eq!(lines[1], "");
+        assert!(lines[2].starts_with("   "));
+        let run = lines.iter().position(|line| *line == "2. Run it").unwrap();
+        assert_eq!(lines[run - 1], "");
+        assert_eq!(lines[run + 1], "");
+        assert_eq!(lines[run + 2], "   Second paragraph.");
+        assert_eq!(lines[run + 3], "");
+        assert_eq!(lines[run + 4], "3. Done");
+    }
+
+    #[test]
+    fn test_render_markdown_tight_list_has_no_blank_lines() {
+        let markdown: Markdown = "- one\n- two\n  - nested".parse().unwrap();
+        let result = strip_ansi(&render_markdown_to_string(&markdown).unwrap());
+        assert_eq!(
+            result.lines().collect::<Vec<_>>(),
+            vec!["● one", "● two", "  ○ nested"]
+        );
+    }
+
+    #[test]
+    fn test_render_markdown_task_progress() {
+        let markdown: Markdown =
+            "- [x] Tag\n- [ ] Publish\n  - [x] Crates\n\nText\n\n- [ ] Announce"
+                .parse()
+                .unwrap();
+        let options = RenderOptions {
+            task_progress: true,
+            ..Default::default()
+        };
+        let result =
+            strip_ansi(&render_markdown_to_string_with_options(&markdown, &options).unwrap());
+        assert!(result.contains("2/3 done"));
+        assert!(result.contains("0/1 done"));
+        assert!(result.contains("Tasks"));
+        assert!(result.contains("2/4 done"));
+
+        let result = render_markdown_to_string(&markdown).unwrap();
+        assert!(!result.contains("done"));
+    }
+
+    fn render_list_source(source: &str, list_numbering: ListNumbering) -> String {
+        let markdown: Markdown = source.parse().unwrap();
+        let options = RenderOptions {
+            list_numbering,
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        render_markdown_with_source(&markdown, source, &mut output, &options).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[rstest]
+    #[case("3. Third\n4. Fourth", vec!["3. Third", "4. Fourth"])]
+    #[case("0. Zero\n1. One", vec!["0. Zero", "1. One"])]
+    #[case("9. Nine\n10. Ten", vec![" 9. Nine", "10. Ten"])]
+    fn test_render_markdown_ordered_list_start(#[case] source: &str, #[case] expected: Vec<&str>) {
+        let result = render_list_source(source, ListNumbering::Decimal);
+        assert_eq!(result.lines().map(strip_ansi).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_render_markdown_ordered_list_in_mkdocs_admonition() {
+        // The re-parsed body's first line must not be read from the document's first line
+        let result = render_list_source(
+            "7. Seven\n\n!!! note\n\n    1. One\n    2. Two",
+            ListNumbering::Decimal,
+        );
+        let result = strip_ansi(&result);
+        assert!(result.contains("7. Seven"));
+        assert!(result.contains("│ 1. One"));
+        assert!(!result.contains("7. One"));
+    }
+
+    #[rstest]
+    #[case(ListNumbering::ByDepth, vec!["1. One", "   a. Sub", "      i. Deep"])]
+    #[case(ListNumbering::Hierarchical, vec!["1. One", "   1.1. Sub", "        1.1.1. Deep"])]
+    fn test_render_markdown_nested_numbering(
+        #[case] numbering: ListNumbering,
+        #[case] expected: Vec<&str>,
+    ) {
+        let result = render_list_source("1. One\n   1. Sub\n      1. Deep", numbering);
+        assert_eq!(result.lines().map(strip_ansi).collect::<Vec<_>>(), expected);
+    }
+
     #[test]
     fn test_render_markdown_mixed_formatting() {
         let markdown: Markdown = "**Bold** and *italic* with `code`".parse().unwrap();
@@ -1267,14 +2780,424 @@ mod tests {
         assert!(result.contains("mixed case tip"));
     }
 
+    #[test]
+    fn test_render_markdown_diff_code_block() {
+        let markdown: Markdown =
+            "```diff\n--- a/lib.rs\n+++ b/lib.rs\n@@ -1 +1 @@\n-old\n+new\n```"
+                .parse()
+                .unwrap();
+        let result = render_markdown_to_string(&markdown).unwrap();
+        assert!(result.contains("old"));
+        assert!(result.contains("new"));
+        // Added and removed lines are painted with a background color
+        assert!(result.contains("\x1b[48;5;22m"));
+        assert!(result.contains("\x1b[48;5;52m"));
+    }
+
+    #[test]
+    fn test_render_markdown_marks_syntax_errors() {
+        let markdown: Markdown = "```rust\nfn main( {}\n```".parse().unwrap();
+        let plain = render_markdown_to_string(&markdown).unwrap();
+        assert!(!plain.contains("\x1b[4;31m"));
+
+        let options = RenderOptions {
+            mark_syntax_errors: true,
+            ..Default::default()
+        };
+        let marked = render_markdown_to_string_with_options(&markdown, &options).unwrap();
+        assert!(marked.contains("\x1b[4;31m"));
+    }
+
+    #[test]
+    fn test_render_markdown_fence_preview() {
+        let markdown: Markdown = "```markdown\n# Nested\n\n- item\n```".parse().unwrap();
+
+        let source = render_markdown_to_string(&markdown).unwrap();
+        assert!(source.contains("# Nested"));
+        assert!(!source.contains("┌─ preview"));
+
+        let options = RenderOptions {
+            preview_markdown: true,
+            ..Default::default()
+        };
+        let preview = render_markdown_to_string_with_options(&markdown, &options).unwrap();
+        assert!(preview.contains("┌─ preview"));
+        assert!(preview.contains("Nested"));
+        assert!(!preview.contains("# Nested"));
+        assert!(preview.contains("●"));
+        // Every rendered line of the nested document stays inside the frame
+        for line in preview.lines().filter(|l| l.contains("item")) {
+            assert!(line.contains("│"));
+        }
+    }
+
+    #[test]
+    fn test_render_markdown_preview_fits_width() {
+        let markdown: Markdown = "```markdown\n# Title\n\n---\n\n| Name | Description |\n| --- | --- |\n| a | a longer cell |\n```"
+            .parse()
+            .unwrap();
+        let options = RenderOptions {
+            preview_markdown: true,
+            width: 30,
+            ..Default::default()
+        };
+        let preview =
+            strip_ansi(&render_markdown_to_string_with_options(&markdown, &options).unwrap());
+        assert!(preview.contains(&format!("  │ {}\n", "─".repeat(26))));
+        for line in preview.lines() {
+            assert!(width::display_width(line) <= 30, "{:?}", line);
+        }
+    }
+
+    #[rstest]
+    #[case("Some ~~old~~ text", "old")]
+    #[case("H~2~O", "H₂O")]
+    #[case("E = mc^2^", "E = mc²")]
+    #[case("A ==key== point", "key")]
+    #[case("# ~~Draft~~ title", "Draft")]
+    #[case("| ~~a~~ |\n| --- |\n| b |", "a")]
+    fn test_render_markdown_inline_styles(#[case] input: &str, #[case] expected: &str) {
+        let markdown: Markdown = input.parse().unwrap();
+        let options = RenderOptions {
+            inline_extensions: true,
+            ..Default::default()
+        };
+        let result = render_markdown_to_string_with_options(&markdown, &options).unwrap();
+        assert!(result.contains(expected), "{:?}", result);
+        assert!(!result.contains('~') && !result.contains('^') && !result.contains("=="));
+    }
+
+    #[rstest]
+    #[case("E = mc^2^ with ==key==")]
+    #[case("If a == b or c == d then x^2")]
+    fn test_render_markdown_inline_extensions_off(#[case] input: &str) {
+        let markdown: Markdown = input.parse().unwrap();
+        let result = strip_ansi(&render_markdown_to_string(&markdown).unwrap());
+        assert!(result.contains(input), "{:?}", result);
+    }
+
+    #[test]
+    fn test_render_markdown_inline_math() {
+        let markdown: Markdown = "Energy $E = mc^2$ holds".parse().unwrap();
+        let result = render_markdown_to_string(&markdown).unwrap();
+        assert!(result.contains("E = mc²"));
+        assert!(!result.contains('$'));
+    }
+
+    #[test]
+    fn test_render_markdown_display_math_centered() {
+        let markdown: Markdown = "$$\n\\frac{a}{b}\n$$".parse().unwrap();
+        let options = RenderOptions {
+            width: 21,
+            ..Default::default()
+        };
+        let result =
+            strip_ansi(&render_markdown_to_string_with_options(&markdown, &options).unwrap());
+        assert!(result.contains("         ───"));
+        assert!(result.contains("a"));
+        assert!(!result.contains("frac"));
+    }
+
+    const FRONT_MATTER_DOC: &str =
+        "---\ntitle: Design notes\nauthor: Jane\ntags: [ml, math]\n---\n\nBody text";
+
+    #[rstest]
+    #[case(FrontMatterMode::Panel, &["╭─ metadata", "Jane", "#ml #math", "Design notes"], &["title:"])]
+    #[case(FrontMatterMode::Raw, &["---", "title", "Jane"], &["metadata"])]
+    #[case(FrontMatterMode::Hide, &["Body text"], &["Jane", "metadata", "---"])]
+    fn test_render_markdown_front_matter(
+        #[case] mode: FrontMatterMode,
+        #[case] present: &[&str],
+        #[case] absent: &[&str],
+    ) {
+        let markdown: Markdown = FRONT_MATTER_DOC.parse().unwrap();
+        let options = RenderOptions {
+            front_matter: mode,
+            ..Default::default()
+        };
+        let result =
+            strip_ansi(&render_markdown_to_string_with_options(&markdown, &options).unwrap());
+        for text in present {
+            assert!(
+                result.contains(text),
+                "{:?} missing from {:?}",
+                text,
+                result
+            );
+        }
+        for text in absent {
+            assert!(!result.contains(text), "{:?} found in {:?}", text, result);
+        }
+    }
+
+    #[test]
+    fn test_render_markdown_front_matter_title_as_h1() {
+        let markdown: Markdown = FRONT_MATTER_DOC.parse().unwrap();
+        let result = strip_ansi(&render_markdown_to_string(&markdown).unwrap());
+        assert!(result.contains("① Design notes"));
+
+        // An existing h1 wins over the front matter title
+        let markdown: Markdown = "---\ntitle: Design notes\n---\n\n# Real title"
+            .parse()
+            .unwrap();
+        let result = strip_ansi(&render_markdown_to_string(&markdown).unwrap());
+        assert!(!result.contains("① Design notes"));
+        assert!(result.contains("① Real title"));
+    }
+
+    #[test]
+    fn test_render_markdown_reference_links() {
+        let markdown: Markdown = "Read the [guide][docs] or [Docs].\n\n![logo][img]\n\n[docs]: https://example.com/docs\n[img]: logo-that-does-not-exist.png"
+            .parse()
+            .unwrap();
+        let result = render_markdown_to_string(&markdown).unwrap();
+        assert!(result.contains(&make_clickable_link("https://example.com/docs", "guide")));
+        assert!(result.contains(&make_clickable_link("https://example.com/docs", "Docs")));
+        assert!(result.contains("logo-that-does-not-exist.png"));
+        // Definition lines are hidden
+        assert!(!result.contains("[docs]:"));
+        assert!(!result.contains("[img]:"));
+    }
+
+    #[test]
+    fn test_render_inline_content_reference_link() {
+        let markdown: Markdown = "| [a][x] |\n| --- |\n| b |\n\n[x]: https://example.com/x"
+            .parse()
+            .unwrap();
+        let result = render_markdown_to_string(&markdown).unwrap();
+        assert!(result.contains(&make_clickable_link("https://example.com/x", "a")));
+    }
+
+    #[test]
+    fn test_render_inline_content_reference_image() {
+        let markdown: Markdown =
+            "[![build][badge]](https://example.com/ci) [![missing][none]](https://example.com)\n\n[badge]: https://example.com/badge.svg"
+                .parse()
+                .unwrap();
+        let result = render_markdown_to_string(&markdown).unwrap();
+        assert!(result.contains(&make_clickable_link("https://example.com/ci", "🖼️ build")));
+        assert!(result.contains("![missing]"));
+    }
+
+    fn render_mdx(source: &str) -> String {
+        let markdown = Markdown::from_mdx_str(source).unwrap();
+        strip_ansi(&render_markdown_to_string(&markdown).unwrap())
+    }
+
+    #[test]
+    fn test_render_mdx_hides_esm() {
+        let result =
+            render_mdx("import { Chart } from './chart'\nexport const meta = {}\n\n# Title");
+        assert!(!result.contains("import"));
+        assert!(!result.contains("export"));
+        assert!(result.contains("Title"));
+    }
+
+    #[test]
+    fn test_render_mdx_callout_component() {
+        let result = render_mdx(
+            "<Callout type=\"warning\" title=\"Careful\">\n\nMind the **gap**.\n\n</Callout>\n\nAfter",
+        );
+        assert!(result.contains("┌─ ⚠️ Warning: Careful"));
+        assert!(result.contains("│ Mind the gap"));
+        assert!(result.contains("└─\n\n"));
+    }
+
+    #[test]
+    fn test_render_mdx_unknown_component() {
+        let result = render_mdx("<Chart data={points} title=\"Sales\" />");
+        assert!(result.contains("┌─ <Chart data={points} title=\"Sales\" />"));
+    }
+
+    #[test]
+    fn test_render_mdx_tabs() {
+        let result = render_mdx(
+            "<Tabs>\n<Tab label=\"npm\">\n\nnpm install\n\n</Tab>\n<Tab label=\"yarn\">\n\nyarn add\n\n</Tab>\n</Tabs>",
+        );
+        assert!(result.contains("┌─ Tabs"));
+        assert!(result.contains("│ ▸ npm"));
+        assert!(result.contains("│ yarn add"));
+    }
+
+    #[rstest]
+    #[case(
+        "<Details summary=\"More\">\n\nHidden text\n\n</Details>",
+        false,
+        "▶ More",
+        false
+    )]
+    #[case(
+        "<Details summary=\"More\">\n\nHidden text\n\n</Details>",
+        true,
+        "▼ More",
+        true
+    )]
+    #[case(
+        "<Details summary=\"More\" open>\n\nHidden text\n\n</Details>",
+        false,
+        "▼ More",
+        true
+    )]
+    fn test_render_mdx_details(
+        #[case] source: &str,
+        #[case] expand_details: bool,
+        #[case] summary: &str,
+        #[case] shows_body: bool,
+    ) {
+        let markdown = Markdown::from_mdx_str(source).unwrap();
+        let options = RenderOptions {
+            expand_details,
+            ..Default::default()
+        };
+        let result =
+            strip_ansi(&render_markdown_to_string_with_options(&markdown, &options).unwrap());
+        assert!(result.contains(summary));
+        assert_eq!(result.contains("  Hidden text"), shows_body);
+    }
+
+    #[test]
+    fn test_render_mdx_expressions() {
+        let result = render_mdx("Total: {count} items\n\n{items.map(render)}");
+        assert!(result.contains("Total: {count} items"));
+        assert!(result.contains("{items.map(render)}"));
+    }
+
+    #[test]
+    fn test_render_markdown_inline_html_elements() {
+        let markdown: Markdown =
+            "Press <kbd>Ctrl</kbd>+<kbd>C</kbd>, H<sub>2</sub>O and x<sup>2</sup>"
+                .parse()
+                .unwrap();
+        let result = render_markdown_to_string(&markdown).unwrap();
+        assert!(result.contains("⟦Ctrl⟧"));
+        assert!(result.contains("⟦C⟧"));
+        assert!(result.contains("H₂O"));
+        assert!(result.contains("x²"));
+        assert!(!result.contains("<kbd>"));
+    }
+
+    #[test]
+    fn test_render_markdown_inline_html_break() {
+        let markdown: Markdown = "first<br>second".parse().unwrap();
+        let result = render_markdown_to_string(&markdown).unwrap();
+        assert!(result.contains("first\nsecond"));
+    }
+
+    #[test]
+    fn test_render_markdown_centered_html_block() {
+        let markdown: Markdown = "<p align=\"center\">\n  <b>Centered</b>\n</p>"
+            .parse()
+            .unwrap();
+        let options = RenderOptions {
+            width: 20,
+            ..Default::default()
+        };
+        let result = render_markdown_to_string_with_options(&markdown, &options).unwrap();
+        assert!(result.contains("      "));
+        assert!(result.contains("Centered"));
+        assert!(!result.contains("<p"));
+    }
+
+    #[rstest]
+    #[case(false, "▶ Install", false)]
+    #[case(true, "▼ Install", true)]
+    fn test_render_markdown_details_section(
+        #[case] expand_details: bool,
+        #[case] summary: &str,
+        #[case] shows_body: bool,
+    ) {
+        let markdown: Markdown =
+            "<details>\n<summary>Install</summary>\n\nRun **cargo install**\n\n</details>\n\nAfter"
+                .parse()
+                .unwrap();
+        let options = RenderOptions {
+            expand_details,
+            ..Default::default()
+        };
+        let result =
+            strip_ansi(&render_markdown_to_string_with_options(&markdown, &options).unwrap());
+        assert!(result.contains(summary));
+        assert_eq!(result.contains("cargo install"), shows_body);
+        assert!(result.contains("After"));
+        assert!(!result.contains("<details>"));
+        assert!(!result.contains("</details>"));
+    }
+
+    #[test]
+    fn test_render_markdown_details_section_fits_width() {
+        let markdown: Markdown =
+            "<details open>\n<summary>More</summary>\n\n---\n\n| Name | Description |\n| --- | --- |\n| a | a longer cell |\n\n</details>"
+                .parse()
+                .unwrap();
+        let options = RenderOptions {
+            width: 20,
+            ..Default::default()
+        };
+        let result =
+            strip_ansi(&render_markdown_to_string_with_options(&markdown, &options).unwrap());
+        assert!(result.contains("  ──────────────────\n"));
+        for line in result.lines() {
+            assert!(width::display_width(line) <= 20, "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn test_render_markdown_nested_details_sections() {
+        let markdown: Markdown = "<details open>\n<summary>Outer</summary>\n\n<details>\n<summary>Inner</summary>\n\nHidden\n\n</details>\n\nShown\n\n</details>"
+            .parse()
+            .unwrap();
+        let result = strip_ansi(&render_markdown_to_string(&markdown).unwrap());
+        assert!(result.contains("▼ Outer"));
+        assert!(result.contains("  ▶ Inner"));
+        assert!(result.contains("  Shown"));
+        assert!(!result.contains("Hidden"));
+    }
+
+    #[test]
+    fn test_render_markdown_unknown_html_is_highlighted() {
+        let markdown: Markdown = "<table>\n<tr><td>cell</td></tr>\n</table>".parse().unwrap();
+        let result = render_markdown_to_string(&markdown).unwrap();
+        assert!(result.contains("table"));
+        assert!(result.contains("cell"));
+    }
+
+    #[test]
+    fn test_render_markdown_footnotes() {
+        let markdown: Markdown =
+            "Alpha[^one] and beta[^two].\n\n[^two]: Second note\n\n[^one]: First note\n\nAfter"
+                .parse()
+                .unwrap();
+        let result = render_markdown_to_string(&markdown).unwrap();
+        assert!(result.contains("¹"));
+        assert!(result.contains("²"));
+        assert!(!result.contains("[^one]"));
+        // References link to the definition's number in the footnotes section
+        assert!(result.contains(&make_clickable_link("#footnote-1", "¹")));
+        assert!(result.contains(&make_clickable_link("#footnote-2", "²")));
+        assert!(result.contains(&make_clickable_link("#footnote-1", "1.")));
+
+        // Definitions are gathered at the end, numbered by first reference
+        let section = result.find("Footnotes").unwrap();
+        assert!(result.find("After").unwrap() < section);
+        let first = result.find("First note").unwrap();
+        let second = result.find("Second note").unwrap();
+        assert!(section < first && first < second);
+    }
+
+    #[test]
+    fn test_render_markdown_without_footnotes() {
+        let markdown: Markdown = "No notes here".parse().unwrap();
+        let result = render_markdown_to_string(&markdown).unwrap();
+        assert!(!result.contains("Footnotes"));
+    }
+
     #[test]
     fn test_render_markdown_html_block() {
         let markdown: Markdown = "<div>Hello HTML</div>".parse().unwrap();
         let result = render_markdown_to_string(&markdown).unwrap();
-        // Should contain the HTML content
+        // Should contain the HTML content, interpreted rather than shown as source
         assert!(result.contains("Hello HTML"));
-        // Should contain some syntax highlighting (colored output)
-        assert!(result.contains("\x1b"));
+        assert!(!result.contains("<div>"));
     }
 
     #[test]
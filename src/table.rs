@@ -0,0 +1,319 @@
+use crate::width;
+use mq_markdown::{Node, TableAlignKind};
+
+/// Narrowest a column is squeezed to before its words are broken
+const MIN_COLUMN_WIDTH: usize = 4;
+/// Longest word that a column keeps unbroken when space is short
+const MAX_WORD_WIDTH: usize = 16;
+
+/// A table assembled from the flat `TableCell`, `TableHeader` and `TableRow` nodes the parser
+/// produces, with each cell's inline content
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Table<'a> {
+    /// Cells of the header row, if the table has one
+    pub(crate) header: Option<Vec<&'a [Node]>>,
+    /// Column alignments from the delimiter row, which may be shorter than the rows
+    pub(crate) alignments: Vec<TableAlignKind>,
+    /// Body rows, which may have fewer or more cells than the header
+    pub(crate) rows: Vec<Vec<&'a [Node]>>,
+}
+
+impl<'a> Table<'a> {
+    /// Build a table from the table nodes of one table, in document order
+    pub(crate) fn from_nodes(nodes: impl IntoIterator<Item = &'a Node>) -> Self {
+        let mut rows: Vec<Vec<&'a [Node]>> = Vec::new();
+        let mut current_row = None;
+        let mut header_rows = None;
+        let mut alignments = Vec::new();
+
+        for node in nodes {
+            match node {
+                Node::TableCell(cell) => {
+                    if current_row != Some(cell.row) {
+                        rows.push(Vec::new());
+                        current_row = Some(cell.row);
+                    }
+                    if let Some(row) = rows.last_mut() {
+                        row.push(&cell.values);
+                    }
+                }
+                Node::TableHeader(header) => {
+                    header_rows = Some(rows.len());
+                    alignments = header.align.clone();
+                    current_row = None;
+                }
+                Node::TableRow(row) => {
+                    rows.push(
+                        row.values
+                            .iter()
+                            .filter_map(|node| match node {
+                                Node::TableCell(cell) => Some(cell.values.as_slice()),
+                                _ => None,
+                            })
+                            .collect(),
+                    );
+                    current_row = None;
+                }
+                _ => {}
+            }
+        }
+
+        // The rows before the delimiter row form the header
+        let header = match header_rows {
+            Some(1..) => Some(rows.remove(0)),
+            _ => None,
+        };
+        Self {
+            header,
+            alignments,
+            rows,
+        }
+    }
+
+    /// Number of columns: the most cells of any row, or of the delimiter row
+    pub(crate) fn columns(&self) -> usize {
+        self.header
+            .iter()
+            .chain(&self.rows)
+            .map(Vec::len)
+            .chain([self.alignments.len()])
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Alignment of a column, `None` for columns past the delimiter row
+    pub(crate) fn alignment(&self, column: usize) -> &TableAlignKind {
+        self.alignments.get(column).unwrap_or(&TableAlignKind::None)
+    }
+}
+
+/// Columns used by the borders and padding of a table with `columns` columns
+pub(crate) fn border_width(columns: usize) -> usize {
+    3 * columns + 1
+}
+
+/// The width a cell asks for when the table has to shrink: its longest word, within
+/// `MIN_COLUMN_WIDTH..=MAX_WORD_WIDTH`
+pub(crate) fn min_width(text: &str) -> usize {
+    let longest_word = text.split(' ').map(width::display_width).max().unwrap_or(0);
+    longest_word.clamp(MIN_COLUMN_WIDTH, MAX_WORD_WIDTH)
+}
+
+/// Fit columns of the given natural and minimum widths into `available` columns.
+///
+/// Columns keep their natural width when the table fits. Otherwise every column gets its
+/// minimum and the remaining space is shared in proportion to how much wider each column
+/// wants to be. Returns `None` when even the minimum widths do not fit.
+pub(crate) fn fit_columns(
+    natural: &[usize],
+    minimum: &[usize],
+    available: usize,
+) -> Option<Vec<usize>> {
+    if natural.iter().sum::<usize>() <= available {
+        return Some(natural.to_vec());
+    }
+
+    let minimum: Vec<usize> = natural
+        .iter()
+        .zip(minimum)
+        .map(|(natural, minimum)| (*minimum).min(*natural))
+        .collect();
+    let spare = available.checked_sub(minimum.iter().sum())?;
+    let wants: Vec<usize> = natural
+        .iter()
+        .zip(&minimum)
+        .map(|(natural, minimum)| natural - minimum)
+        .collect();
+    let total_wants: usize = wants.iter().sum();
+
+    let mut widths: Vec<usize> = minimum
+        .iter()
+        .zip(&wants)
+        .map(|(minimum, want)| minimum + want * spare / total_wants)
+        .collect();
+
+    // Hand out the columns lost to rounding, widest wants first
+    let mut left = available - widths.iter().sum::<usize>();
+    let mut order: Vec<usize> = (0..widths.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(wants[i]));
+    for i in order {
+        if left == 0 {
+            break;
+        }
+        if widths[i] < natural[i] {
+            widths[i] += 1;
+            left -= 1;
+        }
+    }
+    Some(widths)
+}
+
+/// Horizontal alignment of the cells in a column
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl From<&mq_markdown::TableAlignKind> for Align {
+    fn from(kind: &mq_markdown::TableAlignKind) -> Self {
+        match kind {
+            mq_markdown::TableAlignKind::Right => Align::Right,
+            mq_markdown::TableAlignKind::Center => Align::Center,
+            mq_markdown::TableAlignKind::Left | mq_markdown::TableAlignKind::None => Align::Left,
+        }
+    }
+}
+
+/// Pad styled text with spaces to `columns` columns
+pub(crate) fn pad(text: &str, columns: usize, align: Align) -> String {
+    let padding = columns.saturating_sub(width::display_width(text));
+    let (left, right) = match align {
+        Align::Left => (0, padding),
+        Align::Center => (padding / 2, padding - padding / 2),
+        Align::Right => (padding, 0),
+    };
+    format!("{}{}{}", " ".repeat(left), text, " ".repeat(right))
+}
+
+/// Whether styled cell text reads as a number, such as `1,024`, `-3.5`, `$12.00` or `87%`
+pub(crate) fn is_numeric(text: &str) -> bool {
+    let text = width::strip_ansi(text);
+    let text = text.trim();
+    let text = text.strip_prefix(['-', '+', '−']).unwrap_or(text);
+    let text = text.strip_prefix(['$', '€', '£', '¥']).unwrap_or(text);
+    let text = text.strip_suffix('%').unwrap_or(text);
+    text.starts_with(|c: char| c.is_ascii_digit())
+        && text
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, ',' | '.' | '_'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn cell(text: &str, row: usize, column: usize) -> Node {
+        Node::TableCell(mq_markdown::TableCell {
+            values: vec![Node::Text(mq_markdown::Text {
+                value: text.to_string(),
+                position: None,
+            })],
+            column,
+            row,
+            last_cell_in_row: false,
+            last_cell_of_in_table: false,
+            position: None,
+        })
+    }
+
+    fn texts(cells: &[&[Node]]) -> Vec<String> {
+        cells
+            .iter()
+            .map(|values| match values.first() {
+                Some(Node::Text(text)) => text.value.clone(),
+                _ => String::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_table_from_nodes() {
+        let markdown: mq_markdown::Markdown =
+            "| a | b |\n|:--|--:|\n| 1 | 2 |\n| 3 |".parse().unwrap();
+        let table = Table::from_nodes(&markdown.nodes);
+        assert_eq!(texts(table.header.as_ref().unwrap()), vec!["a", "b"]);
+        assert_eq!(
+            table.alignments,
+            vec![TableAlignKind::Left, TableAlignKind::Right]
+        );
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(texts(&table.rows[0]), vec!["1", "2"]);
+        assert_eq!(table.columns(), 2);
+        assert_eq!(table.alignment(5), &TableAlignKind::None);
+    }
+
+    #[test]
+    fn test_table_from_nodes_edge_cases() {
+        // Header only, with an empty delimiter row
+        let nodes = vec![
+            cell("a", 0, 0),
+            cell("b", 0, 1),
+            Node::TableHeader(mq_markdown::TableHeader {
+                align: Vec::new(),
+                position: None,
+            }),
+        ];
+        let table = Table::from_nodes(&nodes);
+        assert_eq!(texts(table.header.as_ref().unwrap()), vec!["a", "b"]);
+        assert!(table.rows.is_empty());
+        assert_eq!(table.columns(), 2);
+
+        // Cells without a delimiter row, with a ragged last row
+        let nodes = vec![cell("a", 0, 0), cell("b", 1, 0), cell("c", 1, 1)];
+        let table = Table::from_nodes(&nodes);
+        assert_eq!(table.header, None);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.columns(), 2);
+
+        assert_eq!(Table::from_nodes(&[]).columns(), 0);
+    }
+
+    #[rstest]
+    #[case(&[5, 10], &[4, 4], 20, Some(vec![5, 10]))]
+    #[case(&[10, 30], &[4, 8], 20, Some(vec![5, 15]))]
+    #[case(&[40, 40], &[16, 16], 30, None)]
+    #[case(&[2, 50], &[4, 4], 12, Some(vec![2, 10]))]
+    fn test_fit_columns(
+        #[case] natural: &[usize],
+        #[case] minimum: &[usize],
+        #[case] available: usize,
+        #[case] expected: Option<Vec<usize>>,
+    ) {
+        let widths = fit_columns(natural, minimum, available);
+        assert_eq!(widths, expected);
+        if let Some(widths) = widths {
+            assert!(widths.iter().sum::<usize>() <= available);
+        }
+    }
+
+    #[rstest]
+    #[case("a b", 4)]
+    #[case("short words only", 5)]
+    #[case("https://example.com/a/very/long/path", 16)]
+    fn test_min_width(#[case] text: &str, #[case] expected: usize) {
+        assert_eq!(min_width(text), expected);
+    }
+
+    #[rstest]
+    #[case("ab", 6, Align::Left, "ab    ")]
+    #[case("ab", 6, Align::Right, "    ab")]
+    #[case("ab", 5, Align::Center, " ab  ")]
+    #[case("\x1b[1mab\x1b[0m", 3, Align::Right, " \x1b[1mab\x1b[0m")]
+    #[case("abcdef", 3, Align::Right, "abcdef")]
+    fn test_pad(
+        #[case] text: &str,
+        #[case] width: usize,
+        #[case] align: Align,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(pad(text, width, align), expected);
+    }
+
+    #[rstest]
+    #[case("1,024", true)]
+    #[case("-3.5", true)]
+    #[case("$12.00", true)]
+    #[case("87%", true)]
+    #[case("\x1b[1m42\x1b[0m", true)]
+    #[case("v1.2", false)]
+    #[case("12 ms", false)]
+    #[case("", false)]
+    #[case("-", false)]
+    fn test_is_numeric(#[case] text: &str, #[case] expected: bool) {
+        assert_eq!(is_numeric(text), expected);
+    }
+}
@@ -0,0 +1,268 @@
+use colored::*;
+use mq_markdown::{Markdown, Node};
+
+/// Cells in a task progress bar
+const PROGRESS_BAR_WIDTH: usize = 20;
+
+/// A task list item (`- [ ]` / `- [x]`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskItem {
+    /// Texts of the headings the task is under, outermost first
+    pub heading_path: Vec<String>,
+    /// Whether the task is checked
+    pub checked: bool,
+    /// Text of the task's first paragraph
+    pub text: String,
+    /// 1-based line of the task in the Markdown source, if known
+    pub line: Option<usize>,
+}
+
+/// Collect every task list item of a document, with the headings it is under.
+///
+/// # Examples
+///
+/// ```rust
+/// use mqv::task_items;
+/// use mq_markdown::Markdown;
+///
+/// let markdown: Markdown = "# Release\n\n- [x] Tag\n- [ ] Publish".parse().unwrap();
+/// let tasks = task_items(&markdown);
+/// assert_eq!(tasks.len(), 2);
+/// assert_eq!(tasks[1].heading_path, vec!["Release".to_string()]);
+/// assert!(!tasks[1].checked);
+/// ```
+pub fn task_items(markdown: &Markdown) -> Vec<TaskItem> {
+    let mut tasks = Vec::new();
+    let mut headings: Vec<(u8, String)> = Vec::new();
+
+    for node in &markdown.nodes {
+        match node {
+            Node::Heading(heading) => {
+                headings.retain(|(depth, _)| *depth < heading.depth);
+                headings.push((heading.depth, plain_text(&heading.values)));
+            }
+            node => {
+                let path: Vec<String> = headings.iter().map(|(_, text)| text.clone()).collect();
+                collect_tasks(std::slice::from_ref(node), &path, &mut tasks);
+            }
+        }
+    }
+    tasks
+}
+
+fn collect_tasks(nodes: &[Node], heading_path: &[String], tasks: &mut Vec<TaskItem>) {
+    for node in nodes {
+        match node {
+            Node::List(list) => {
+                if let Some(checked) = list.checked {
+                    let text = list
+                        .values
+                        .iter()
+                        .find_map(|value| match value {
+                            Node::Fragment(fragment) => Some(plain_text(&fragment.values)),
+                            _ => None,
+                        })
+                        .unwrap_or_default();
+                    tasks.push(TaskItem {
+                        heading_path: heading_path.to_vec(),
+                        checked,
+                        text,
+                        line: list.position.as_ref().map(|p| p.start.line),
+                    });
+                }
+                collect_tasks(&list.values, heading_path, tasks);
+            }
+            Node::Blockquote(blockquote) => collect_tasks(&blockquote.values, heading_path, tasks),
+            _ => {}
+        }
+    }
+}
+
+/// Text content of inline nodes, without formatting
+fn plain_text(nodes: &[Node]) -> String {
+    let mut text = String::new();
+    for node in nodes {
+        match node {
+            Node::Text(value) => text.push_str(&value.value),
+            Node::CodeInline(code) => text.push_str(&code.value),
+            Node::Strong(strong) => text.push_str(&plain_text(&strong.values)),
+            Node::Emphasis(emphasis) => text.push_str(&plain_text(&emphasis.values)),
+            Node::Delete(delete) => text.push_str(&plain_text(&delete.values)),
+            Node::Link(link) => text.push_str(&plain_text(&link.values)),
+            Node::Break(_) => text.push(' '),
+            _ => {}
+        }
+    }
+    text.trim().to_string()
+}
+
+/// Checked and total task counts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Progress {
+    pub(crate) done: usize,
+    pub(crate) total: usize,
+}
+
+impl Progress {
+    /// Count the tasks of the given list items and the lists nested in them
+    pub(crate) fn count<'a>(items: impl IntoIterator<Item = &'a mq_markdown::List>) -> Self {
+        let mut progress = Self::default();
+        for item in items {
+            progress.add_item(item);
+        }
+        progress
+    }
+
+    fn add_item(&mut self, item: &mq_markdown::List) {
+        if let Some(checked) = item.checked {
+            self.total += 1;
+            self.done += usize::from(checked);
+        }
+        for value in &item.values {
+            if let Node::List(list) = value {
+                self.add_item(list);
+            }
+        }
+    }
+
+    /// A `▕██████░░░░▏ 3/5 done` bar
+    pub(crate) fn bar(&self) -> String {
+        let filled = (self.done * PROGRESS_BAR_WIDTH)
+            .checked_div(self.total)
+            .unwrap_or(0);
+        format!(
+            "▕{}{}▏ {}/{} done",
+            "█".repeat(filled).green(),
+            "░".repeat(PROGRESS_BAR_WIDTH - filled).bright_black(),
+            self.done,
+            self.total
+        )
+    }
+}
+
+/// Toggle the checkbox of the task item that starts on a 1-based line of `source`, the text
+/// `markdown` was parsed from, returning the new source.
+///
+/// Returns `None` unless a task list item of the document starts on that line, so checkboxes
+/// in code blocks or HTML are never touched.
+///
+/// # Examples
+///
+/// ```rust
+/// use mqv::toggle_task;
+/// use mq_markdown::Markdown;
+///
+/// let source = "- [ ] Publish\n- [x] Tag\n\n```\n- [ ] code\n```\n";
+/// let markdown: Markdown = source.parse().unwrap();
+/// assert_eq!(
+///     toggle_task(&markdown, source, 1).unwrap(),
+///     "- [x] Publish\n- [x] Tag\n\n```\n- [ ] code\n```\n"
+/// );
+/// assert!(toggle_task(&markdown, source, 5).is_none());
+/// ```
+pub fn toggle_task(markdown: &Markdown, source: &str, line: usize) -> Option<String> {
+    if !task_items(markdown)
+        .iter()
+        .any(|task| task.line == Some(line))
+    {
+        return None;
+    }
+
+    let start = source
+        .split_inclusive('\n')
+        .take(line.checked_sub(1)?)
+        .map(str::len)
+        .sum::<usize>();
+    let text = source[start..].split('\n').next()?;
+
+    // The checkbox follows the list marker, after any quote markers and indentation
+    let marker_end = text
+        .find(|c: char| !c.is_whitespace() && c != '>')
+        .and_then(|marker| {
+            let rest = &text[marker..];
+            let digits = rest.chars().take_while(char::is_ascii_digit).count();
+            match rest[digits..].chars().next()? {
+                '-' | '*' | '+' if digits == 0 => Some(marker + 1),
+                '.' | ')' if digits > 0 => Some(marker + digits + 1),
+                _ => None,
+            }
+        })?;
+    let offset = marker_end + text[marker_end..].find('[')?;
+    if !text[marker_end..offset].trim().is_empty() {
+        return None;
+    }
+    let replacement = match text.get(offset..offset + 3)? {
+        "[ ]" => "[x]",
+        "[x]" | "[X]" => "[ ]",
+        _ => return None,
+    };
+
+    let mut toggled = source.to_string();
+    toggled.replace_range(start + offset..start + offset + 3, replacement);
+    Some(toggled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn test_task_items_heading_path() {
+        let markdown: Markdown = "# Release\n\n## Backend\n\n- [x] Migrate **db**\n- Not a task\n  - [ ] Nested\n\n## Frontend\n\n- [ ] Build"
+            .parse()
+            .unwrap();
+        let tasks = task_items(&markdown);
+        assert_eq!(
+            tasks
+                .iter()
+                .map(|task| (
+                    task.heading_path.join(" > "),
+                    task.checked,
+                    task.text.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Release > Backend".to_string(), true, "Migrate db"),
+                ("Release > Backend".to_string(), false, "Nested"),
+                ("Release > Frontend".to_string(), false, "Build"),
+            ]
+        );
+        assert_eq!(tasks[0].line, Some(5));
+    }
+
+    #[test]
+    fn test_progress_count() {
+        let markdown: Markdown = "- [x] a\n- [ ] b\n  - [x] c\n- d".parse().unwrap();
+        let items = markdown.nodes.iter().filter_map(|node| match node {
+            Node::List(list) => Some(list),
+            _ => None,
+        });
+        assert_eq!(Progress::count(items), Progress { done: 2, total: 3 });
+    }
+
+    #[rstest]
+    #[case(Progress { done: 7, total: 12 }, "7/12 done", 11)]
+    #[case(Progress { done: 0, total: 3 }, "0/3 done", 0)]
+    #[case(Progress { done: 0, total: 0 }, "0/0 done", 0)]
+    fn test_progress_bar(#[case] progress: Progress, #[case] label: &str, #[case] filled: usize) {
+        let bar = progress.bar();
+        assert!(bar.ends_with(label));
+        assert_eq!(bar.matches('█').count(), filled);
+    }
+
+    #[rstest]
+    #[case("- [ ] a", 1, Some("- [x] a"))]
+    #[case("  * [X] a", 1, Some("  * [ ] a"))]
+    #[case("> 3. [x] a", 1, Some("> 3. [ ] a"))]
+    #[case("intro\n\n- [ ] a\n", 3, Some("intro\n\n- [x] a\n"))]
+    #[case("- a [ ] b", 1, None)]
+    #[case("[ ] not a list", 1, None)]
+    #[case("- [ ] a", 2, None)]
+    #[case("```\n- [ ] code\n```\n", 2, None)]
+    #[case("<div>\n- [ ] html\n</div>\n", 2, None)]
+    fn test_toggle_task(#[case] source: &str, #[case] line: usize, #[case] expected: Option<&str>) {
+        let markdown: Markdown = source.parse().unwrap();
+        assert_eq!(toggle_task(&markdown, source, line).as_deref(), expected);
+    }
+}
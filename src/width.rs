@@ -0,0 +1,252 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// A piece of styled text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Token<'a> {
+    /// An ANSI CSI or OSC escape sequence, which takes no columns
+    Escape(&'a str),
+    /// A user-perceived character, such as `é`, `漢` or `👩‍💻`
+    Grapheme(&'a str),
+}
+
+/// Split styled text into escape sequences and grapheme clusters
+pub(crate) fn tokens(text: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut plain_start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c != '\x1b' {
+            continue;
+        }
+        let mut end = text.len();
+        match chars.next() {
+            // CSI: ends with a final byte in @..~
+            Some((_, '[')) => {
+                for (i, c) in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        end = i + c.len_utf8();
+                        break;
+                    }
+                }
+            }
+            // OSC: ends with BEL or ST (ESC \)
+            Some((_, ']')) => {
+                while let Some((i, c)) = chars.next() {
+                    if c == '\x07' {
+                        end = i + 1;
+                        break;
+                    }
+                    if c == '\x1b' && chars.next_if(|(_, c)| *c == '\\').is_some() {
+                        end = i + 2;
+                        break;
+                    }
+                }
+            }
+            Some((i, c)) => end = i + c.len_utf8(),
+            None => {}
+        }
+        tokens.extend(
+            text[plain_start..start]
+                .graphemes(true)
+                .map(Token::Grapheme),
+        );
+        tokens.push(Token::Escape(&text[start..end]));
+        plain_start = end;
+    }
+    tokens.extend(text[plain_start..].graphemes(true).map(Token::Grapheme));
+    tokens
+}
+
+/// Columns a grapheme cluster occupies: 2 for East Asian wide characters and emoji, 0 for
+/// control characters
+pub(crate) fn grapheme_width(grapheme: &str) -> usize {
+    grapheme.width().min(2)
+}
+
+/// Number of terminal columns text occupies, ignoring ANSI escape sequences
+pub(crate) fn display_width(text: &str) -> usize {
+    tokens(text)
+        .into_iter()
+        .map(|token| match token {
+            Token::Escape(_) => 0,
+            Token::Grapheme(grapheme) => grapheme_width(grapheme),
+        })
+        .sum()
+}
+
+/// Text without its ANSI escape sequences
+pub(crate) fn strip_ansi(text: &str) -> String {
+    tokens(text)
+        .into_iter()
+        .filter_map(|token| match token {
+            Token::Grapheme(grapheme) => Some(grapheme),
+            Token::Escape(_) => None,
+        })
+        .collect()
+}
+
+/// Styles that are open at some point of the text, so a wrapped line can close them and
+/// the next line can reopen them
+#[derive(Debug, Default)]
+struct OpenStyles {
+    sgr: Vec<String>,
+    link: Option<String>,
+}
+
+impl OpenStyles {
+    fn update(&mut self, escape: &str) {
+        if let Some(params) = escape
+            .strip_prefix("\x1b[")
+            .and_then(|rest| rest.strip_suffix('m'))
+        {
+            if params.is_empty() || params == "0" {
+                self.sgr.clear();
+            } else {
+                self.sgr.push(escape.to_string());
+            }
+        } else if let Some(rest) = escape.strip_prefix("\x1b]8;") {
+            let url = rest.split_once(';').map_or("", |(_, url)| url);
+            let url = url
+                .trim_end_matches(['\x07', '\\'])
+                .trim_end_matches('\x1b');
+            self.link = (!url.is_empty()).then(|| escape.to_string());
+        }
+    }
+
+    fn close(&self, line: &mut String) {
+        if self.link.is_some() {
+            line.push_str("\x1b]8;;\x1b\\");
+        }
+        if !self.sgr.is_empty() {
+            line.push_str("\x1b[0m");
+        }
+    }
+
+    fn reopen(&self) -> String {
+        let mut line: String = self.sgr.concat();
+        if let Some(link) = &self.link {
+            line.push_str(link);
+        }
+        line
+    }
+}
+
+/// Word-wrap styled text into lines of at most `width` columns. Words longer than the width
+/// are broken between graphemes, and styles and hyperlinks continue on the following lines.
+pub(crate) fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let tokens = tokens(text);
+    let words = tokens.split(|token| *token == Token::Grapheme(" "));
+
+    let mut lines = Vec::new();
+    let mut styles = OpenStyles::default();
+    let mut line = String::new();
+    let mut line_width = 0;
+
+    for word in words {
+        let word_width: usize = word
+            .iter()
+            .map(|token| match token {
+                Token::Escape(_) => 0,
+                Token::Grapheme(grapheme) => grapheme_width(grapheme),
+            })
+            .sum();
+        if word_width == 0 {
+            // Keep the styles of empty words without adding spaces
+            for token in word {
+                if let Token::Escape(escape) = token {
+                    line.push_str(escape);
+                    styles.update(escape);
+                }
+            }
+            continue;
+        }
+
+        if line_width > 0 && line_width + 1 + word_width > width {
+            styles.close(&mut line);
+            lines.push(std::mem::replace(&mut line, styles.reopen()));
+            line_width = 0;
+        }
+        if line_width > 0 {
+            line.push(' ');
+            line_width += 1;
+        }
+
+        for token in word {
+            match token {
+                Token::Escape(escape) => {
+                    line.push_str(escape);
+                    styles.update(escape);
+                }
+                Token::Grapheme(grapheme) => {
+                    let grapheme_width = grapheme_width(grapheme);
+                    if line_width > 0 && line_width + grapheme_width > width {
+                        styles.close(&mut line);
+                        lines.push(std::mem::replace(&mut line, styles.reopen()));
+                        line_width = 0;
+                    }
+                    line.push_str(grapheme);
+                    line_width += grapheme_width;
+                }
+            }
+        }
+    }
+    lines.push(line);
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("plain", 5)]
+    #[case("\x1b[1;34mbold\x1b[0m", 4)]
+    #[case("\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\", 4)]
+    #[case("日本語", 6)]
+    #[case("ｶﾀｶﾅ", 4)]
+    #[case("e\u{301}", 1)]
+    #[case("☑️", 2)]
+    #[case("👍🏽", 2)]
+    #[case("👩‍💻 dev", 6)]
+    #[case("🔗 \x1b]8;;https://example.com\x1b\\リンク\x1b]8;;\x1b\\", 9)]
+    fn test_display_width(#[case] text: &str, #[case] expected: usize) {
+        assert_eq!(display_width(text), expected);
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(
+            strip_ansi("\x1b[1mbold\x1b[0m \x1b]8;;https://example.com\x07link\x1b]8;;\x07"),
+            "bold link"
+        );
+    }
+
+    #[rstest]
+    #[case("short", 10, vec!["short"])]
+    #[case("the quick brown fox", 10, vec!["the quick", "brown fox"])]
+    #[case("abcdefghij", 4, vec!["abcd", "efgh", "ij"])]
+    #[case("日本語のテキスト", 6, vec!["日本語", "のテキ", "スト"])]
+    #[case("ab日本", 3, vec!["ab", "日", "本"])]
+    #[case("", 4, vec![""])]
+    fn test_wrap(#[case] text: &str, #[case] width: usize, #[case] expected: Vec<&str>) {
+        assert_eq!(wrap(text, width), expected);
+    }
+
+    #[test]
+    fn test_wrap_reopens_styles() {
+        let lines = wrap("\x1b[1mbold words\x1b[0m plain", 5);
+        assert_eq!(
+            lines,
+            vec!["\x1b[1mbold\x1b[0m", "\x1b[1mwords\x1b[0m", "plain"]
+        );
+
+        let link = "\x1b]8;;https://example.com\x1b\\a link\x1b]8;;\x1b\\";
+        let lines = wrap(link, 4);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].starts_with("\x1b]8;;https://example.com\x1b\\"));
+        assert!(lines[0].ends_with("\x1b]8;;\x1b\\"));
+    }
+}